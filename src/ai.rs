@@ -1,37 +1,127 @@
-use crate::settings::AppConfig;
+use crate::{cache::ResponseCache, settings::AppConfig};
 use anyhow::{bail, Context, Result};
+use chrono::Duration;
 use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 
-pub fn generate_summary(config: &AppConfig, commit_logs: String) -> Result<String> {
-    let client = Client::new();
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CachePolicy {
+    Use,
+    Refresh,
+    Bypass,
+}
 
-    let final_prompt = config
-        .ai
-        .prompt
+fn build_prompt(template: &str, config: &AppConfig, commit_logs: &str) -> String {
+    template
         .replace("{LANGUAGE}", &config.ai.language)
-        .replace("{LOGS}", &commit_logs);
+        .replace("{LOGS}", commit_logs)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self {
+            role: "system".to_string(),
+            content: content.into(),
+        }
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: "user".to_string(),
+            content: content.into(),
+        }
+    }
 
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self {
+            role: "assistant".to_string(),
+            content: content.into(),
+        }
+    }
+}
+
+pub fn initial_messages(config: &AppConfig, commit_logs: &str) -> Vec<ChatMessage> {
+    vec![
+        ChatMessage::system("You are a helpful assistant."),
+        ChatMessage::user(build_prompt(&config.ai.prompt, config, commit_logs)),
+    ]
+}
+
+pub fn generate_with_prompt(
+    config: &AppConfig,
+    template: &str,
+    commit_logs: &str,
+    policy: CachePolicy,
+) -> Result<String> {
+    let messages = vec![
+        ChatMessage::system("You are a helpful assistant."),
+        ChatMessage::user(build_prompt(template, config, commit_logs)),
+    ];
+    chat(config, &messages, policy)
+}
+
+pub fn generate_summary(
+    config: &AppConfig,
+    commit_logs: String,
+    policy: CachePolicy,
+) -> Result<String> {
+    chat(config, &initial_messages(config, &commit_logs), policy)
+}
+
+pub fn chat(config: &AppConfig, messages: &[ChatMessage], policy: CachePolicy) -> Result<String> {
     let payload = json!({
         "model": config.ai.model,
-        "messages": [
-        {
-            "role": "system",
-            "content": "You are a helpful assistant."
-        },
-        {
-            "role": "user",
-            "content": final_prompt
-        }
-    ],
-    "stream": false
+        "messages": messages,
+        "stream": false
     });
 
+    complete(config, &payload, policy)
+}
+
+fn complete(config: &AppConfig, payload: &serde_json::Value, policy: CachePolicy) -> Result<String> {
+    let policy = if config.cache.enabled {
+        policy
+    } else {
+        CachePolicy::Bypass
+    };
+    if policy == CachePolicy::Bypass {
+        return send(config, payload);
+    }
+
+    let cache = ResponseCache::open()?;
+    let key = ResponseCache::key(&config.ai.api_url, payload);
+    let ttl = Duration::hours(config.cache.ttl_hours as i64);
+
+    if policy == CachePolicy::Use
+        && let Some(cached) = cache.get(&key, ttl)
+    {
+        return Ok(cached);
+    }
+
+    let response = send(config, payload)?;
+
+    if let Err(e) = cache.put(&key, &response) {
+        eprintln!("Failed to cache AI response: {}", e);
+    }
+    Ok(response)
+}
+
+fn send(config: &AppConfig, payload: &serde_json::Value) -> Result<String> {
+    let client = Client::new();
+    let api_key = config.ai.resolve_api_key()?;
+
     let res = client
         .post(&config.ai.api_url)
-        .header("Authorization", format!("Bearer {}", config.ai.api_key))
+        .header("Authorization", format!("Bearer {}", api_key))
         .header("Content-Type", "application/json")
-        .json(&payload)
+        .json(payload)
         .send()
         .context("Api request failed. check url.")?;
 
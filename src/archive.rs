@@ -0,0 +1,288 @@
+use crate::db::{Database, ManuelEntry, ScanRoot};
+use crate::migrations;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub const ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Archive {
+    pub archive_version: u32,
+    pub exported_at: DateTime<Utc>,
+    #[serde(default)]
+    pub home: Option<PathBuf>,
+    pub database: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<Value>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct MergeReport {
+    pub notes_added: usize,
+    pub notes_skipped: usize,
+    pub notes_renumbered: Vec<(u64, u64)>,
+    pub summaries_added: usize,
+    pub repos_added: usize,
+    pub roots_added: usize,
+}
+
+pub fn build(db: &Database, settings: Option<Value>, home: Option<PathBuf>) -> Result<Archive> {
+    Ok(Archive {
+        archive_version: ARCHIVE_VERSION,
+        exported_at: Utc::now(),
+        home,
+        database: serde_json::to_value(db)?,
+        settings,
+    })
+}
+
+pub fn write(path: &Path, archive: &Archive) -> Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path).with_context(|| format!("Failed to write {:?}", path))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(serde_json::to_string_pretty(archive)?.as_bytes())
+        .with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(())
+}
+
+pub fn parse(content: &str) -> Result<(Archive, Database)> {
+    let archive: Archive = serde_json::from_str(content).context("Not a yestergit archive.")?;
+    if archive.archive_version > ARCHIVE_VERSION {
+        bail!(
+            "The archive uses format version {}, but this yestergit only reads up to {}. Please upgrade yestergit.",
+            archive.archive_version,
+            ARCHIVE_VERSION
+        );
+    }
+
+    let mut database = archive.database.clone();
+    migrations::migrate(&mut database)?;
+    let db: Database = serde_json::from_value(database).context("The archived database is invalid.")?;
+    Ok((archive, db))
+}
+
+fn remap(path: &Path, from: &Path, to: &Path) -> PathBuf {
+    match path.strip_prefix(from) {
+        Ok(rest) => to.join(rest),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+pub fn remap_home(db: &mut Database, from: &Path, to: &Path) {
+    if from == to {
+        return;
+    }
+    for path in db.repositories.iter_mut() {
+        *path = remap(path, from, to);
+    }
+    db.repo_meta = std::mem::take(&mut db.repo_meta)
+        .into_iter()
+        .map(|(path, meta)| (remap(&path, from, to), meta))
+        .collect();
+    for path in db.untracked.iter_mut() {
+        *path = remap(path, from, to);
+    }
+    for root in db.roots.iter_mut() {
+        root.path = remap(&root.path, from, to);
+        root.mtimes = BTreeMap::new();
+        root.last_scan = None;
+    }
+}
+
+fn same_note(a: &ManuelEntry, b: &ManuelEntry) -> bool {
+    a.message == b.message && a.date == b.date && a.tags == b.tags && a.repo == b.repo && a.kind == b.kind
+}
+
+pub fn merge(local: &mut Database, incoming: Database) -> MergeReport {
+    let mut report = MergeReport::default();
+
+    let after_max = local.entries.iter().chain(&incoming.entries).map(|e| e.id).max().unwrap_or(0) + 1;
+    local.next_note_id = local.next_note_id.max(incoming.next_note_id).max(after_max);
+    for mut note in incoming.entries {
+        if local.entries.iter().any(|e| same_note(e, &note)) {
+            report.notes_skipped += 1;
+            continue;
+        }
+        if local.entries.iter().any(|e| e.id == note.id) {
+            report.notes_renumbered.push((note.id, local.next_note_id));
+            note.id = local.next_note_id;
+            local.next_note_id += 1;
+        }
+        local.entries.push(note);
+        report.notes_added += 1;
+    }
+    local.entries.sort_by_key(|e| e.id);
+
+    for record in incoming.history {
+        if !local.history.contains(&record) {
+            local.history.push(record);
+            report.summaries_added += 1;
+        }
+    }
+    local.history.sort_by_key(|h| h.date);
+
+    for path in incoming.repositories {
+        if !local.repositories.contains(&path) {
+            local.repositories.push(path);
+            report.repos_added += 1;
+        }
+    }
+    for (path, meta) in incoming.repo_meta {
+        local.repo_meta.entry(path).or_insert(meta);
+    }
+
+    for path in incoming.untracked {
+        if !local.untracked.contains(&path) && !local.repositories.contains(&path) {
+            local.untracked.push(path);
+        }
+    }
+
+    for root in incoming.roots {
+        if !local.roots.iter().any(|r| r.path == root.path) {
+            local.roots.push(ScanRoot {
+                path: root.path,
+                last_scan: None,
+                mtimes: BTreeMap::new(),
+            });
+            report.roots_added += 1;
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{NoteKind, RepoMeta, SummaryRecord};
+    use crate::settings;
+    use chrono::TimeZone;
+
+    fn at(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip_and_merge() {
+        let mut laptop = Database::default();
+        laptop.repositories.push(PathBuf::from("/home/old/work/api"));
+        laptop.meta_mut(Path::new("/home/old/work/api")).alias = Some("API".to_string());
+        laptop.roots.push(ScanRoot {
+            path: PathBuf::from("/home/old/work"),
+            last_scan: Some(at(2)),
+            mtimes: BTreeMap::new(),
+        });
+        laptop.add_note("shared".to_string(), at(1), Vec::new(), None);
+        laptop.add_note("standup, edited".to_string(), at(2), Vec::new(), None);
+        laptop.add_note("laptop only".to_string(), at(4), Vec::new(), None);
+        laptop.add_activity("Switched to spike".to_string(), at(5), "api".to_string());
+        laptop.history.push(SummaryRecord {
+            date: at(2),
+            model: "gpt".to_string(),
+            summary: "Did things.".to_string(),
+        });
+
+        let mut settings = serde_json::json!({
+            "ai": { "api_key": "secret", "model": "gpt" },
+            "email": { "password": "hunter22" },
+            "post": { "slack": "https://hooks.slack.com/services/T/B/x", "teams": "" }
+        });
+        settings::strip_secrets(&mut settings);
+        let archive = build(&laptop, Some(settings), Some(PathBuf::from("/home/old"))).unwrap();
+        let content = serde_json::to_string(&archive).unwrap();
+
+        let (archive, mut incoming) = parse(&content).unwrap();
+        let archived = archive.settings.unwrap();
+        assert_eq!(archived["ai"]["api_key"], "");
+        assert_eq!(archived["email"]["password"], "");
+        assert_eq!(archived["post"]["slack"], "");
+        assert_eq!(archived["ai"]["model"], "gpt");
+        remap_home(&mut incoming, Path::new("/home/old"), Path::new("/Users/new"));
+
+        let mut desktop = Database::default();
+        desktop.add_note("shared".to_string(), at(1), Vec::new(), None);
+        desktop.add_note("standup".to_string(), at(2), Vec::new(), None);
+        desktop.history.push(laptop.history[0].clone());
+
+        let report = merge(&mut desktop, incoming);
+        assert_eq!(
+            report,
+            MergeReport {
+                notes_added: 3,
+                notes_skipped: 1,
+                notes_renumbered: vec![(2, 5)],
+                summaries_added: 0,
+                repos_added: 1,
+                roots_added: 1,
+            }
+        );
+
+        let messages: Vec<(u64, &str)> = desktop.entries.iter().map(|e| (e.id, e.message.as_str())).collect();
+        assert_eq!(
+            messages,
+            vec![
+                (1, "shared"),
+                (2, "standup"),
+                (3, "laptop only"),
+                (4, "Switched to spike"),
+                (5, "standup, edited")
+            ]
+        );
+        assert_eq!(desktop.entries[3].kind, NoteKind::Activity);
+        assert_eq!(desktop.add_entry("next".to_string()), 6);
+        assert_eq!(desktop.repositories, vec![PathBuf::from("/Users/new/work/api")]);
+        assert_eq!(
+            desktop.repo_meta[Path::new("/Users/new/work/api")],
+            RepoMeta {
+                alias: Some("API".to_string()),
+                ..Default::default()
+            }
+        );
+        assert_eq!(desktop.roots[0].path, PathBuf::from("/Users/new/work"));
+
+        let again = merge(&mut desktop, parse(&content).unwrap().1);
+        assert_eq!(again.notes_skipped, 4);
+        assert_eq!(again.notes_added, 0);
+        assert!(again.notes_renumbered.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_archive_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("backup.json");
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        write(&path, &build(&Database::default(), None, None).unwrap()).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert!(parse(&fs::read_to_string(&path).unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_newer_archive_is_rejected() {
+        let content = serde_json::json!({
+            "archive_version": ARCHIVE_VERSION + 1,
+            "exported_at": "2026-03-01T00:00:00Z",
+            "database": { "repositories": [] }
+        });
+        assert!(parse(&content.to_string()).is_err());
+    }
+}
@@ -0,0 +1,139 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Serialize, Deserialize, Debug)]
+struct CachedResponse {
+    created_at: DateTime<Utc>,
+    response: String,
+}
+
+pub struct ResponseCache {
+    dir: PathBuf,
+}
+
+impl ResponseCache {
+    pub fn open() -> Result<Self> {
+        let proj_dirs = ProjectDirs::from("com", "yestergit-cli", "yestergit")
+            .context("Cache path does not exists.")?;
+        Ok(Self::new(proj_dirs.cache_dir().join("responses")))
+    }
+
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn key(api_url: &str, payload: &serde_json::Value) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(api_url.as_bytes());
+        hasher.update([0u8]);
+        hasher.update(payload.to_string().as_bytes());
+        hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    pub fn get(&self, key: &str, ttl: Duration) -> Option<String> {
+        let path = self.entry_path(key);
+        let content = fs::read_to_string(&path).ok()?;
+        let cached: CachedResponse = serde_json::from_str(&content).ok()?;
+
+        if cached.created_at + ttl < Utc::now() {
+            let _ = fs::remove_file(&path);
+            return None;
+        }
+        Some(cached.response)
+    }
+
+    pub fn put(&self, key: &str, response: &str) -> Result<()> {
+        fs::create_dir_all(&self.dir).context("Failed to create cache dir")?;
+
+        let content = serde_json::to_string(&CachedResponse {
+            created_at: Utc::now(),
+            response: response.to_string(),
+        })?;
+
+        let mut temp_file = tempfile::Builder::new()
+            .prefix("yestergit_cache_")
+            .suffix(".tmp")
+            .tempfile_in(&self.dir)
+            .context("Failed to create temp file for cache entry")?;
+        temp_file
+            .write_all(content.as_bytes())
+            .context("Failed to write cache entry")?;
+        temp_file
+            .persist(self.entry_path(key))
+            .context("Failed to store cache entry")?;
+        Ok(())
+    }
+
+    pub fn clear(&self) -> Result<usize> {
+        if !self.dir.exists() {
+            return Ok(0);
+        }
+
+        let mut removed = 0;
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                fs::remove_file(&path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.dir
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_key_depends_on_payload_and_url() {
+        let payload = json!({"model": "llama3", "messages": []});
+        let other = json!({"model": "llama3.1", "messages": []});
+
+        let key = ResponseCache::key("http://a", &payload);
+        assert_eq!(key, ResponseCache::key("http://a", &payload));
+        assert_ne!(key, ResponseCache::key("http://b", &payload));
+        assert_ne!(key, ResponseCache::key("http://a", &other));
+    }
+
+    #[test]
+    fn test_get_put_clear() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = ResponseCache::new(temp_dir.path().join("responses"));
+
+        assert_eq!(cache.get("abc", Duration::hours(1)), None);
+
+        cache.put("abc", "summary").unwrap();
+        assert_eq!(
+            cache.get("abc", Duration::hours(1)),
+            Some("summary".to_string())
+        );
+        assert_eq!(cache.get("abc", Duration::seconds(-1)), None);
+        assert_eq!(cache.get("abc", Duration::hours(1)), None);
+
+        cache.put("abc", "summary").unwrap();
+        cache.put("def", "other").unwrap();
+        assert_eq!(cache.clear().unwrap(), 2);
+        assert_eq!(cache.get("def", Duration::hours(1)), None);
+    }
+}
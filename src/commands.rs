@@ -1,14 +1,20 @@
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use colored::Colorize;
 use rayon::prelude::*;
-use std::path::PathBuf;
-use tabled::{Table, Tabled};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use tabled::{builder::Builder, Table, Tabled};
 use chrono::{DateTime, Datelike, Duration, Local, NaiveTime, Utc};
 use crate::{
-    ai,
-    config::Args,
-    db::{Database, ManuelEntry},
-    git_ops, scanner, settings,
+    ai::{self, CachePolicy},
+    archive,
+    cache::ResponseCache,
+    config::{Args, OutputFormat},
+    db::{Database, ManuelEntry, NoteKind, RepoMeta, SummaryRecord, TrackedRepo},
+    storage::Backend,
+    conventional, dates, discovery, doctor, email, git_ops, hooks, hours, html, post::{self, Sink}, profile, refine, scanner, settings, stats, team,
 };
 
 #[derive(Tabled)]
@@ -22,146 +28,1826 @@ struct ReportRow {
     #[tabled(rename = "Source / Repo")]
     source: String,
 
+    #[tabled(rename = "Change")]
+    change: String,
+
+    #[tabled(rename = "Ticket")]
+    ticket: String,
+
     #[tabled(rename = "Message")]
     message: String,
 
-    #[tabled(rename = "Hash")]
-    hash: String,
+    #[tabled(rename = "Hash")]
+    hash: String,
+}
+
+#[derive(Tabled)]
+struct NoteRow {
+    #[tabled(rename = "ID")]
+    id: u64,
+
+    #[tabled(rename = "Time")]
+    time: String,
+
+    #[tabled(rename = "Repo")]
+    repo: String,
+
+    #[tabled(rename = "Tags")]
+    tags: String,
+
+    #[tabled(rename = "Message")]
+    message: String,
+}
+
+#[derive(Tabled)]
+struct StatusRow {
+    #[tabled(rename = "Repo")]
+    repo: String,
+
+    #[tabled(rename = "Path")]
+    path: String,
+
+    #[tabled(rename = "Exists")]
+    exists: String,
+
+    #[tabled(rename = "Readable")]
+    readable: String,
+
+    #[tabled(rename = "HEAD")]
+    head: String,
+
+    #[tabled(rename = "Last commit")]
+    last_commit: String,
+}
+
+#[derive(Tabled)]
+struct TeamRow {
+    #[tabled(rename = "Time")]
+    time: String,
+
+    #[tabled(rename = "Repo")]
+    repo: String,
+
+    #[tabled(rename = "Message")]
+    message: String,
+
+    #[tabled(rename = "Hash")]
+    hash: String,
+}
+
+#[derive(Serialize)]
+struct JsonEvent {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<u64>,
+    date: DateTime<Local>,
+    #[serde(rename = "type")]
+    event_type: &'static str,
+    source: String,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scope: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    breaking: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tickets: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct JsonMember {
+    name: String,
+    commits: Vec<JsonEvent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+}
+
+#[derive(Serialize)]
+struct JsonReport {
+    since: DateTime<Local>,
+    events: Vec<JsonEvent>,
+    errors: Vec<JsonFailure>,
+}
+
+#[derive(Serialize)]
+struct JsonTeamReport {
+    since: DateTime<Local>,
+    members: Vec<JsonMember>,
+    errors: Vec<JsonFailure>,
+}
+
+#[derive(Serialize)]
+struct JsonTimeEntry {
+    day: String,
+    repo: String,
+    ticket: Option<String>,
+    hours: f64,
+}
+
+#[derive(Serialize)]
+struct JsonHours {
+    since: DateTime<Local>,
+    total_hours: f64,
+    by_repo: BTreeMap<String, f64>,
+    by_ticket: BTreeMap<String, f64>,
+    by_day: BTreeMap<String, f64>,
+    entries: Vec<JsonTimeEntry>,
+    errors: Vec<JsonFailure>,
+}
+
+#[derive(Serialize)]
+struct JsonStats<'a> {
+    #[serde(flatten)]
+    activity: &'a stats::ActivityStats,
+    errors: Vec<JsonFailure>,
+}
+
+enum TimelineEvent {
+    Commit(Box<git_ops::CommitLog>, TrackedRepo),
+    Note(ManuelEntry),
+}
+
+impl TimelineEvent {
+    fn date(&self) -> DateTime<Local> {
+        match self {
+            TimelineEvent::Commit(c, _) => c.date,
+            TimelineEvent::Note(n) => DateTime::from(n.date),
+        }
+    }
+
+    fn to_json(&self) -> JsonEvent {
+        match self {
+            TimelineEvent::Commit(c, repo) => commit_to_json(c, &repo.name),
+            TimelineEvent::Note(n) => JsonEvent {
+                id: Some(n.id),
+                date: DateTime::from(n.date),
+                event_type: match n.kind {
+                    NoteKind::Manual => "note",
+                    NoteKind::Activity => "activity",
+                },
+                source: n.repo.clone().unwrap_or_else(|| "-".to_string()),
+                message: n.message.clone(),
+                author: None,
+                hash: None,
+                kind: None,
+                scope: None,
+                breaking: false,
+                tickets: conventional::ticket_keys(&n.message),
+                tags: n.tags.clone(),
+            },
+        }
+    }
+}
+
+fn commit_to_json(c: &git_ops::CommitLog, repo_name: &str) -> JsonEvent {
+    JsonEvent {
+        id: None,
+        date: c.date,
+        event_type: "commit",
+        source: repo_name.to_string(),
+        message: c.message.clone(),
+        author: Some(c.author.clone()),
+        hash: Some(c.hash.clone()),
+        kind: c.kind.clone(),
+        scope: c.scope.clone(),
+        breaking: c.breaking,
+        tickets: c.tickets.clone(),
+        tags: Vec::new(),
+    }
+}
+
+fn change_label(c: &git_ops::CommitLog) -> String {
+    let Some(kind) = &c.kind else {
+        return "-".to_string();
+    };
+    let mut label = kind.clone();
+    if let Some(scope) = &c.scope {
+        label.push_str(&format!("({})", scope));
+    }
+    if c.breaking {
+        label.push('!');
+    }
+    label
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_line(fields: &[&str]) -> String {
+    fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(",")
+}
+
+fn tags_label(tags: &[String]) -> String {
+    if tags.is_empty() {
+        "-".to_string()
+    } else {
+        tags.iter().map(|t| format!("#{}", t)).collect::<Vec<_>>().join(" ")
+    }
+}
+
+fn tickets_label(tickets: &[String]) -> String {
+    if tickets.is_empty() {
+        "-".to_string()
+    } else {
+        tickets.join(", ")
+    }
+}
+
+fn commit_matches(c: &git_ops::CommitLog, args: &Args) -> bool {
+    if let Some(kind) = &args.commit_type
+        && !c.kind.as_deref().is_some_and(|k| k.eq_ignore_ascii_case(kind))
+    {
+        return false;
+    }
+    if let Some(ticket) = &args.ticket
+        && !c.tickets.iter().any(|t| t.eq_ignore_ascii_case(ticket))
+    {
+        return false;
+    }
+    true
+}
+
+fn note_matches(n: &ManuelEntry, args: &Args) -> bool {
+    if args.commit_type.is_some() {
+        return false;
+    }
+    match &args.ticket {
+        Some(ticket) => conventional::ticket_keys(&n.message)
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(ticket)),
+        None => true,
+    }
+}
+
+fn note_in_repos(n: &ManuelEntry, repos: &[TrackedRepo], args: &Args) -> bool {
+    if args.group.is_none() {
+        return true;
+    }
+    n.repo
+        .as_deref()
+        .is_some_and(|r| repos.iter().any(|repo| repo_label_matches(r, repo)))
+}
+
+fn format_commit_groups(commits: &[git_ops::CommitLog]) -> String {
+    let mut groups: Vec<(String, Vec<&git_ops::CommitLog>)> = Vec::new();
+    for c in commits {
+        let label = match c.tickets.first() {
+            Some(ticket) => format!("Ticket {}", ticket),
+            None => conventional::kind_label(c.kind.as_deref()).to_string(),
+        };
+        match groups.iter_mut().find(|(l, _)| *l == label) {
+            Some((_, group)) => group.push(c),
+            None => groups.push((label, vec![c])),
+        }
+    }
+
+    let mut out = String::new();
+    for (label, group) in groups {
+        out.push_str(&format!("{}:\n", label));
+        for c in group {
+            let breaking = if c.breaking { " (breaking change)" } else { "" };
+            out.push_str(&format!("- {}{}\n", c.message.trim(), breaking));
+        }
+    }
+    out
+}
+
+fn repo_name(repo_path: &PathBuf) -> String {
+    std::fs::canonicalize(repo_path)
+        .ok()
+        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| repo_path.to_string_lossy().to_string())
+}
+
+fn is_recorded_commit(n: &ManuelEntry, hashes: &HashSet<String>) -> bool {
+    n.kind == NoteKind::Activity
+        && hooks::committed_hash(&n.message).is_some_and(|hash| hashes.contains(hash))
+}
+
+fn repo_label_matches(label: &str, repo: &TrackedRepo) -> bool {
+    label.eq_ignore_ascii_case(&repo.name)
+        || repo
+            .path
+            .file_name()
+            .is_some_and(|n| label.eq_ignore_ascii_case(&n.to_string_lossy()))
+}
+
+struct RepoFailure {
+    repo: String,
+    path: PathBuf,
+    error: anyhow::Error,
+}
+
+#[derive(Serialize)]
+struct JsonFailure {
+    repo: String,
+    path: PathBuf,
+    error: String,
+    causes: Vec<String>,
+}
+
+impl RepoFailure {
+    fn to_json(&self) -> JsonFailure {
+        JsonFailure {
+            repo: self.repo.clone(),
+            path: self.path.clone(),
+            error: self.error.to_string(),
+            causes: self.error.chain().skip(1).map(|c| c.to_string()).collect(),
+        }
+    }
+}
+
+type Collected<'a> = (Vec<(&'a TrackedRepo, git_ops::CommitLog)>, Vec<RepoFailure>);
+
+fn collect_commits<'a, F>(repos: &'a [TrackedRepo], args: &Args, fetch: F) -> Collected<'a>
+where
+    F: Fn(&PathBuf) -> Result<Vec<git_ops::CommitLog>> + Sync,
+{
+    let results: Vec<(&TrackedRepo, Result<Vec<git_ops::CommitLog>>)> = repos
+        .par_iter()
+        .map(|repo| (repo, fetch(&repo.path)))
+        .collect();
+
+    let mut commits = Vec::new();
+    let mut failures = Vec::new();
+    for (repo, result) in results {
+        match result {
+            Ok(logs) => commits.extend(
+                logs.into_iter()
+                    .filter(|c| commit_matches(c, args))
+                    .map(|c| (repo, c)),
+            ),
+            Err(error) => failures.push(RepoFailure {
+                repo: repo.name.clone(),
+                path: repo.path.clone(),
+                error,
+            }),
+        }
+    }
+    (commits, failures)
+}
+
+fn print_failures(failures: &[RepoFailure], args: &Args) {
+    if failures.is_empty() {
+        return;
+    }
+    eprintln!();
+    eprintln!(
+        "{}",
+        format!("Warnings: {} repositories could not be read", failures.len()).yellow()
+    );
+    for failure in failures {
+        eprintln!("  {} ({}): {}", failure.repo, failure.path.display(), failure.error);
+        if args.verbose {
+            for cause in failure.error.chain().skip(1) {
+                eprintln!("      caused by: {}", cause);
+            }
+        }
+    }
+    if !args.verbose {
+        eprintln!("  Run with --verbose to see the full causes.");
+    }
+}
+
+fn check_failures(failures: &[RepoFailure], args: &Args) -> Result<()> {
+    if args.strict && !failures.is_empty() {
+        print_failures(failures, args);
+        bail!("{} repositories could not be read.", failures.len());
+    }
+    Ok(())
+}
+
+fn scan_options(config: settings::ScanConfig) -> scanner::ScanOptions {
+    let mut options = scanner::ScanOptions {
+        ignore: config.ignore,
+        max_depth: config.max_depth,
+        follow_links: config.follow_links,
+        submodules: config.submodules,
+    };
+    if let Some(dirs) = directories::BaseDirs::new() {
+        options
+            .ignore
+            .extend(scanner::read_ignore_file(&dirs.home_dir().join(scanner::IGNORE_FILE)));
+    }
+    options
+}
+
+pub fn scan(
+    path: PathBuf,
+    max_depth: Option<usize>,
+    follow_links: bool,
+    ignore: Vec<String>,
+    submodules: bool,
+) -> Result<()> {
+    let app_config = settings::load()?;
+
+    let mut options = scan_options(app_config.scan);
+    options.ignore.extend(ignore);
+    if max_depth.is_some() {
+        options.max_depth = max_depth;
+    }
+    options.follow_links |= follow_links;
+    options.submodules |= submodules;
+
+    let result = scanner::scan_repositories(path, &options)?;
+    let repos = result.repos;
+    println!(
+        "Scanned {} directories, found {} repositories.",
+        result.dirs_visited,
+        repos.len()
+    );
+    for (worktree, main) in &result.worktrees {
+        println!("Worktree {} belongs to {}", worktree.display(), main.display());
+    }
+
+    if repos.is_empty() {
+        println!("No repos found.");
+        return Ok(());
+    }
+
+    Database::update(|db| {
+        db.untracked.retain(|path| !repos.contains(path));
+        db.add_repos(repos);
+        Ok(())
+    })?;
+    println!("Repositories added to local database.");
+    Ok(())
+}
+
+pub fn check(path: PathBuf, args: &Args) -> Result<()> {
+    let repo = TrackedRepo {
+        name: repo_name(&path),
+        path,
+        meta: RepoMeta::default(),
+    };
+    let notes = Database::notes_between(Some(get_since_date(args.days)), None)?;
+    print_report(vec![repo], notes, args)
+}
+
+pub fn list(status: bool, args: &Args) -> Result<()> {
+    let db = Database::load_index()?;
+    if !status {
+        println!("Tracked repos:");
+        for repo in db.tracked_repos() {
+            let mut labels = Vec::new();
+            if repo.meta.alias.is_some() {
+                labels.push(repo.name.clone());
+            }
+            if let Some(group) = &repo.meta.group {
+                labels.push(format!("group: {}", group));
+            }
+            if !repo.meta.enabled {
+                labels.push("disabled".to_string());
+            }
+
+            if labels.is_empty() {
+                println!(" - {:?}", repo.path);
+            } else {
+                println!(" - {:?} ({})", repo.path, labels.join(", "));
+            }
+        }
+        return Ok(());
+    }
+
+    let health: Vec<(TrackedRepo, git_ops::RepoHealth)> = db
+        .tracked_repos()
+        .into_par_iter()
+        .map(|repo| {
+            let health = git_ops::repo_health(&repo.path);
+            (repo, health)
+        })
+        .collect();
+
+    if args.format == OutputFormat::Json {
+        let output: Vec<serde_json::Value> = health
+            .iter()
+            .map(|(repo, h)| {
+                serde_json::json!({
+                    "repo": repo.name,
+                    "path": repo.path,
+                    "exists": h.exists,
+                    "readable": h.readable,
+                    "head": h.has_head,
+                    "last_commit": h.last_commit,
+                    "error": h.error,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    let yes_no = |ok: bool| if ok { "yes".green() } else { "no".red() }.to_string();
+    let rows: Vec<StatusRow> = health
+        .into_iter()
+        .map(|(repo, h)| StatusRow {
+            repo: repo.name,
+            path: repo.path.to_string_lossy().to_string(),
+            exists: yes_no(h.exists),
+            readable: yes_no(h.readable),
+            head: yes_no(h.has_head),
+            last_commit: match (h.last_commit, h.error) {
+                (Some(date), _) => date.format("%d/%m/%Y %H:%M").to_string(),
+                (None, Some(error)) => error,
+                (None, None) => "-".to_string(),
+            },
+        })
+        .collect();
+    println!("{}", Table::new(rows));
+    Ok(())
+}
+
+pub fn untrack(target: String, all: bool) -> Result<()> {
+    let found = Database::update(|db| {
+        let found = db.find_repos(&target);
+
+        if found.is_empty() {
+            bail!("No tracked repository matches {:?}.", target);
+        }
+        if found.len() > 1 && !all {
+            let paths: Vec<String> = found.iter().map(|p| format!(" - {:?}", p)).collect();
+            bail!(
+                "{:?} matches several repositories, pass a full path or --all:\n{}",
+                target,
+                paths.join("\n")
+            );
+        }
+
+        db.untrack_repos(&found);
+        Ok(found)
+    })?;
+    for path in found {
+        println!("Untracked {:?}.", path);
+    }
+    Ok(())
+}
+
+pub fn repo(
+    target: String,
+    alias: Option<Option<String>>,
+    group: Option<Option<String>>,
+    enabled: Option<bool>,
+) -> Result<()> {
+    let db = Database::load_index()?;
+    let found = db.find_repos(&target);
+
+    let path = match found.as_slice() {
+        [] => bail!("No tracked repository matches {:?}.", target),
+        [path] => path.clone(),
+        _ => bail!(
+            "{:?} matches several repositories, pass a full path instead.",
+            target
+        ),
+    };
+
+    if alias.is_none() && group.is_none() && enabled.is_none() {
+        let repo = db
+            .tracked_repos()
+            .into_iter()
+            .find(|r| r.path == path)
+            .expect("repository was just found");
+        println!("Path:    {:?}", repo.path);
+        println!("Name:    {}", repo.name);
+        println!("Group:   {}", repo.meta.group.as_deref().unwrap_or("-"));
+        println!("Enabled: {}", repo.meta.enabled);
+        return Ok(());
+    }
+
+    Database::update(|db| {
+        if let Some(Some(alias)) = &alias
+            && let Some(owner) = db.alias_owner(&path, alias)
+        {
+            bail!("{:?} is already the name of {:?}, pick another alias.", alias, owner);
+        }
+        let meta = db.meta_mut(&path);
+        if let Some(alias) = alias {
+            meta.alias = alias;
+        }
+        if let Some(group) = group {
+            meta.group = group;
+        }
+        if let Some(enabled) = enabled {
+            meta.enabled = enabled;
+        }
+        if *meta == RepoMeta::default() {
+            db.repo_meta.remove(&path);
+        }
+        Ok(())
+    })?;
+    println!("Repository {:?} updated.", path);
+    Ok(())
+}
+
+pub fn prune(dry_run: bool) -> Result<()> {
+    let db = Database::load_index()?;
+
+    let broken: Vec<(PathBuf, String)> = db
+        .repositories
+        .par_iter()
+        .filter_map(|path| {
+            let health = git_ops::repo_health(path);
+            if health.is_healthy() {
+                None
+            } else {
+                Some((path.clone(), health.error.unwrap_or_default()))
+            }
+        })
+        .collect();
+
+    if broken.is_empty() {
+        println!("All tracked repositories are healthy.");
+        return Ok(());
+    }
+
+    for (path, reason) in &broken {
+        let verb = if dry_run { "Would remove" } else { "Removed" };
+        println!("{} {:?} ({})", verb, path, reason);
+    }
+
+    if !dry_run {
+        let paths: Vec<PathBuf> = broken.into_iter().map(|(path, _)| path).collect();
+        Database::update(|db| {
+            db.remove_repos(&paths);
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+pub fn roots_add(path: PathBuf) -> Result<()> {
+    if Database::update(|db| db.add_root(&path))? {
+        println!("Added scan root {:?}.", path);
+        rescan_roots(true)
+    } else {
+        println!("{:?} is already a scan root.", path);
+        Ok(())
+    }
+}
+
+pub fn roots_rm(path: PathBuf) -> Result<()> {
+    Database::update(|db| {
+        if !db.remove_root(&path) {
+            bail!("{:?} is not a scan root.", path);
+        }
+        Ok(())
+    })?;
+    println!("Removed scan root {:?}. Its repositories stay tracked.", path);
+    Ok(())
+}
+
+pub fn roots_list() -> Result<()> {
+    let db = Database::load_index()?;
+    if db.roots.is_empty() {
+        println!("No scan roots. Add one with `yestergit roots add <path>`.");
+        return Ok(());
+    }
+
+    println!("Scan roots:");
+    for root in &db.roots {
+        let last_scan = root
+            .last_scan
+            .map(|date| date.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| "never".to_string());
+        println!(" - {:?} (last scan: {})", root.path, last_scan);
+    }
+    Ok(())
+}
+
+pub fn roots_scan() -> Result<()> {
+    rescan_roots(true)
+}
+
+fn rescan_roots(force: bool) -> Result<()> {
+    let mut db = Database::load_index()?;
+    if db.roots.is_empty() {
+        return Ok(());
+    }
+
+    let app_config = settings::load()?;
+    let interval = Duration::minutes(app_config.scan.rescan_interval_minutes as i64);
+    let options = scan_options(app_config.scan);
+
+    let report = discovery::rescan(&mut db, &options, Utc::now(), interval, force)?;
+    if !report.checked.is_empty() {
+        Database::update(|latest| {
+            discovery::merge(latest, &db, &report);
+            Ok(())
+        })?;
+    }
+
+    for repo in &report.added {
+        eprintln!("{} {:?}", "New repository:".green(), repo);
+    }
+    for repo in &report.missing {
+        eprintln!(
+            "{} {:?} (run `yestergit prune` to untrack it)",
+            "Missing repository:".yellow(),
+            repo
+        );
+    }
+    if force {
+        println!(
+            "Scanned {} root(s): {} new, {} missing.",
+            report.scanned.len(),
+            report.added.len(),
+            report.missing.len()
+        );
+    }
+    Ok(())
+}
+
+pub fn note(
+    message: String,
+    at: Option<String>,
+    tags: Vec<String>,
+    repo: Option<String>,
+) -> Result<()> {
+    let date = match at {
+        Some(at) => dates::parse_when(&at, Local::now())?.with_timezone(&Utc),
+        None => Utc::now(),
+    };
+
+    let id = Database::update(|db| {
+        if let Some(repo) = &repo
+            && !db
+                .tracked_repos()
+                .iter()
+                .any(|r| repo_label_matches(repo, r))
+        {
+            eprintln!("Warning: no tracked repository is named {:?}.", repo);
+        }
+
+        Ok(db.add_note(message, date, tags, repo))
+    })?;
+    println!("Note #{} saved. It will appear in your next daily report.", id);
+    Ok(())
+}
+
+pub fn note_list(tag: Option<String>, repo: Option<String>, activity: bool, args: &Args) -> Result<()> {
+    let since_utc = args.days.map(|days| get_since_date(Some(days)));
+
+    let mut notes: Vec<ManuelEntry> = Database::notes_between(since_utc, None)?
+        .into_iter()
+        .filter(|n| since_utc.is_none_or(|since| n.date > since))
+        .filter(|n| n.kind.is_manual() != activity)
+        .filter(|n| tag.as_ref().is_none_or(|t| n.tags.iter().any(|nt| nt.eq_ignore_ascii_case(t))))
+        .filter(|n| {
+            repo.as_ref()
+                .is_none_or(|r| n.repo.as_deref().is_some_and(|nr| nr.eq_ignore_ascii_case(r)))
+        })
+        .collect();
+    notes.sort_by_key(|n| n.date);
+
+    if args.format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&notes)?);
+        return Ok(());
+    }
+
+    if notes.is_empty() {
+        println!("No notes found.");
+        return Ok(());
+    }
+
+    let rows: Vec<NoteRow> = notes
+        .into_iter()
+        .map(|n| NoteRow {
+            id: n.id,
+            time: DateTime::<Local>::from(n.date)
+                .format("%d/%m/%Y %H:%M")
+                .to_string(),
+            repo: n.repo.unwrap_or_else(|| "-".to_string()),
+            tags: tags_label(&n.tags),
+            message: n.message,
+        })
+        .collect();
+    println!("{}", Table::new(rows));
+    Ok(())
+}
+
+pub fn note_edit(
+    id: u64,
+    message: Option<String>,
+    at: Option<String>,
+    tags: Vec<String>,
+    clear_tags: bool,
+    repo: Option<String>,
+    clear_repo: bool,
+) -> Result<()> {
+    let date = match at {
+        Some(at) => Some(dates::parse_when(&at, Local::now())?.with_timezone(&Utc)),
+        None => None,
+    };
+
+    Database::update(|db| {
+        let Some(entry) = db.find_entry_mut(id) else {
+            bail!("No note with id #{}.", id);
+        };
+
+        if let Some(message) = message {
+            entry.message = message;
+        }
+        if let Some(date) = date {
+            entry.date = date;
+        }
+        if clear_tags || !tags.is_empty() {
+            entry.tags = tags;
+        }
+        if clear_repo {
+            entry.repo = None;
+        } else if repo.is_some() {
+            entry.repo = repo;
+        }
+        Ok(())
+    })?;
+    println!("Note #{} updated.", id);
+    Ok(())
+}
+
+pub fn note_rm(id: u64) -> Result<()> {
+    Database::update(|db| match db.remove_entry(id) {
+        Some(_) => Ok(()),
+        None => bail!("No note with id #{}.", id),
+    })?;
+    println!("Note #{} removed.", id);
+    Ok(())
+}
+
+pub fn config(
+    set_key: Option<String>,
+    set_url: Option<String>,
+    set_model: Option<String>,
+    set_prompt: Option<String>,
+    set_lang: Option<String>,
+) -> Result<()> {
+    let updates = [
+        ("ai.api_url", set_url, "API URL updated."),
+        ("ai.api_key", set_key, "API Key updated."),
+        ("ai.model", set_model, "AI Model updated."),
+        ("ai.prompt", set_prompt, "Prompt changed."),
+        ("ai.language", set_lang, "Language changed."),
+    ];
+    if updates.iter().all(|(_, value, _)| value.is_none()) {
+        return config_get(None);
+    }
+
+    let mut app_config = settings::load_file()?;
+    for (key, value, message) in updates {
+        if let Some(value) = value {
+            app_config = settings::set(&app_config, key, &value)?;
+            println!("{}", message);
+            warn_env_override(key);
+        }
+    }
+    settings::store(&app_config)?;
+    println!("Settings saved.");
+    warn_plaintext_key(&app_config)?;
+    Ok(())
+}
+
+fn warn_plaintext_key(file_config: &settings::AppConfig) -> Result<()> {
+    let path = settings::config_path()?;
+    let secrets = settings::stored_secrets(file_config)?;
+    if !secrets.is_empty() && settings::is_world_readable(&path) {
+        eprintln!(
+            "{}",
+            format!(
+                "Warning: {} stored in plain text in {:?}, which other users can read. Run `chmod 600` on it.",
+                secrets.join(", "),
+                path
+            )
+            .yellow()
+        );
+        if secrets.contains(&"ai.api_key") {
+            eprintln!("You can also use ai.api_key_cmd, ai.api_key_file or ai.api_key_env instead of ai.api_key.");
+        }
+    }
+    Ok(())
+}
+
+fn display_setting(key: &str, value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) if settings::SECRET_KEYS.contains(&key) => settings::mask(s),
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn warn_env_override(key: &str) {
+    let name = settings::env_name(key);
+    if std::env::var_os(&name).is_some() {
+        eprintln!(
+            "{}",
+            format!("{} is set and overrides this value.", name).yellow()
+        );
+    }
+}
+
+pub fn config_get(key: Option<String>) -> Result<()> {
+    let app_config = settings::load()?;
+
+    if let Some(key) = key {
+        let value = settings::get(&app_config, &key)?;
+        println!("{}", display_setting(&key, &value));
+        return Ok(());
+    }
+
+    if let Some(name) = profile::active() {
+        println!("Profile: {}", name);
+    }
+    println!("Config file: {:?}", settings::config_path()?);
+    if let Ok(db_path) = Database::get_path() {
+        println!("Database file: {:?}", db_path);
+    }
+    println!();
+    for (key, value) in settings::entries(&app_config)? {
+        let name = settings::env_name(&key);
+        let source = if std::env::var_os(&name).is_some() {
+            format!(" (from {})", name).dimmed().to_string()
+        } else {
+            String::new()
+        };
+        println!("{} = {}{}", key, display_setting(&key, &value), source);
+    }
+    warn_plaintext_key(&settings::load_file()?)
+}
+
+pub fn config_set(key: &str, value: &str) -> Result<()> {
+    let app_config = settings::set(&settings::load_file()?, key, value)?;
+    settings::store(&app_config)?;
+    println!("{} updated.", key);
+    warn_env_override(key);
+    warn_plaintext_key(&app_config)
+}
+
+pub fn config_unset(key: &str) -> Result<()> {
+    let app_config = settings::unset(&settings::load_file()?, key)?;
+    settings::store(&app_config)?;
+    println!("{} reset to its default.", key);
+    warn_env_override(key);
+    Ok(())
+}
+
+pub fn summarize(
+    no_cache: bool,
+    regenerate: bool,
+    interactive: bool,
+    save: bool,
+    sinks: &[Sink],
+    yes: bool,
+    args: &Args,
+) -> Result<()> {
+    rescan_roots(false)?;
+    let db = Database::load_index()?;
+    let app_config = settings::load()?;
+    for sink in sinks {
+        sink.url(&app_config.post)?;
+    }
+
+    let repos = db.active_repos(args.group.as_deref());
+    let notes = Database::notes_between(Some(get_since_date(args.days)), None)?;
+    let logs = collect_logs_as_string(repos, notes, args)?;
+    if logs.trim().is_empty() {
+        println!("There are no logs.");
+        return Ok(());
+    }
+
+    let policy = if no_cache {
+        CachePolicy::Bypass
+    } else if regenerate {
+        CachePolicy::Refresh
+    } else {
+        CachePolicy::Use
+    };
+
+    println!("AI generating summary... ({})", app_config.ai.model);
+
+    let messages = ai::initial_messages(&app_config, &logs);
+    let summary = match ai::chat(&app_config, &messages, policy) {
+        Ok(summary) => summary,
+        Err(e) => {
+            eprintln!("Failed to generate report: {}", e);
+            return Ok(());
+        }
+    };
+    refine::print_summary(&summary);
+
+    let accepted = if interactive {
+        refine::run(&app_config, messages, summary, policy)?
+    } else {
+        Some(summary)
+    };
+
+    if let Some(final_summary) = accepted {
+        if interactive || save {
+            Database::update(|db| {
+                db.add_summary(app_config.ai.model.clone(), final_summary.clone());
+                Ok(())
+            })?;
+            println!("Summary saved to history.");
+        }
+        post_summary(&app_config, sinks, &final_summary, yes)?;
+    }
+    Ok(())
+}
+
+fn confirm(question: &str) -> Result<bool> {
+    if !std::io::stdin().is_terminal() {
+        bail!("Cannot ask for confirmation without a terminal. Pass --yes to post anyway.");
+    }
+    print!("{} [y/N] ", question);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+fn post_summary(config: &settings::AppConfig, sinks: &[Sink], summary: &str, yes: bool) -> Result<()> {
+    let title = format!("Daily update {}", Local::now().format("%d/%m/%Y"));
+    let mut failed = 0;
+    for sink in sinks {
+        let url = sink.url(&config.post)?;
+        let host = url::Url::parse(&url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_default();
+        if !yes && !confirm(&format!("Post the summary to {} ({})?", sink, host))? {
+            println!("Skipped {}.", sink);
+            continue;
+        }
+        match post::send(&url, &sink.payload(&title, summary)) {
+            Ok(()) => println!("Posted to {}.", sink),
+            Err(e) => {
+                eprintln!("Failed to post to {}: {}", sink, e);
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        bail!("{} of {} posts failed.", failed, sinks.len());
+    }
+    Ok(())
+}
+
+pub fn email(
+    to: &[String],
+    cc: &[String],
+    dry_run: bool,
+    no_cache: bool,
+    args: &Args,
+) -> Result<()> {
+    rescan_roots(false)?;
+    let db = Database::load_index()?;
+    let app_config = settings::load()?;
+    let since_utc = get_since_date(args.days);
+
+    let to = if to.is_empty() { &app_config.email.to } else { to };
+    let cc = if cc.is_empty() { &app_config.email.cc } else { cc };
+
+    let repos = db.active_repos(args.group.as_deref());
+    let notes = Database::notes_between(Some(since_utc), None)?;
+    let (events, failures) = collect_timeline(&repos, notes, args, false)?;
+    print_failures(&failures, args);
+
+    let summary = if args.no_summary || events.is_empty() {
+        None
+    } else {
+        let policy = if no_cache {
+            CachePolicy::Bypass
+        } else {
+            CachePolicy::Use
+        };
+        let summary = summarize_events(&app_config, &repos, &events, policy)
+            .context("Failed to generate the summary. Use --no-summary to send the report without it.")?;
+        Some(summary)
+    };
+
+    let since_label = since_utc.with_timezone(&Local).format("%d/%m/%Y");
+    let today_label = Local::now().format("%d/%m/%Y");
+    let report = email::EmailReport {
+        subject: format!("Status report {} - {}", since_label, today_label),
+        heading: format!("Status report since {}", since_label),
+        summary,
+        lines: events
+            .iter()
+            .map(|event| match event {
+                TimelineEvent::Commit(c, repo) => email::ReportLine {
+                    time: c.date.format("%d/%m %H:%M").to_string(),
+                    kind: "Git",
+                    source: repo.name.clone(),
+                    message: c.message.clone(),
+                },
+                TimelineEvent::Note(n) => email::ReportLine {
+                    time: event.date().format("%d/%m %H:%M").to_string(),
+                    kind: n.kind.label(),
+                    source: n.repo.clone().unwrap_or_else(|| "-".to_string()),
+                    message: n.message.clone(),
+                },
+            })
+            .collect(),
+        warnings: failures
+            .iter()
+            .map(|f| format!("{}: {}", f.repo, f.error))
+            .collect(),
+    };
+
+    let message = email::build_message(&app_config.email, to, cc, &report)?;
+    if dry_run {
+        println!("{}", String::from_utf8_lossy(&message.formatted()));
+        return Ok(());
+    }
+    email::send(&app_config.email, &message)?;
+    println!("Report sent to {}.", to.join(", "));
+    Ok(())
 }
 
-enum TimelineEvent {
-    Commit(git_ops::CommitLog, String),
-    Note(ManuelEntry),
+pub fn cache_clear() -> Result<()> {
+    let cache = ResponseCache::open()?;
+    let removed = cache.clear()?;
+    println!("Removed {} cached responses from {:?}.", removed, cache.path());
+    Ok(())
 }
 
-impl TimelineEvent {
-    fn date(&self) -> DateTime<Local> {
-        match self {
-            TimelineEvent::Commit(c, _) => c.date,
-            TimelineEvent::Note(n) => DateTime::from(n.date),
+pub fn db_migrate(to: Backend) -> Result<()> {
+    match Database::migrate_storage(to)? {
+        Some(backup) => {
+            println!("Database moved to {:?}.", Database::get_path()?);
+            println!("The previous file was kept as {:?}.", backup);
         }
+        None => println!("The database already uses {:?} storage.", to),
     }
+    Ok(())
 }
 
-pub fn scan(path: PathBuf) -> Result<()> {
-    let repos = scanner::scan_repositories(path)?;
+pub fn export(file: PathBuf, include_secrets: bool) -> Result<()> {
+    let db = Database::load()?;
+    let app_config = settings::load_file()?;
 
-    if repos.is_empty() {
-        println!("No repos found.");
-        return Ok(());
+    let mut settings = serde_json::to_value(&app_config)?;
+    if !include_secrets {
+        settings::strip_secrets(&mut settings);
     }
+    let home = directories::BaseDirs::new().map(|dirs| dirs.home_dir().to_path_buf());
+    let archive = archive::build(&db, Some(settings), home)?;
 
-    let mut db = Database::load()?;
-    db.add_repos(repos);
-    db.save()?;
-    println!("Repositories added to local database.");
+    archive::write(&file, &archive)?;
+    println!(
+        "Exported {} notes, {} summaries and {} repositories to {:?}.",
+        db.entries.len(),
+        db.history.len(),
+        db.repositories.len(),
+        file
+    );
+    if include_secrets {
+        println!("{}", "The archive contains your API key, webhook URLs and SMTP password, keep it private.".yellow());
+    }
     Ok(())
 }
 
-pub fn check(path: PathBuf, args: &Args) -> Result<()> {
-    let db = Database::load()?;
-    print_report(vec![path], db.entries, args)
+pub fn import(file: PathBuf, with_settings: bool) -> Result<()> {
+    let content = std::fs::read_to_string(&file).with_context(|| format!("Failed to read {:?}", file))?;
+    let (archive, mut incoming) = archive::parse(&content)?;
+
+    if let (Some(from), Some(dirs)) = (&archive.home, directories::BaseDirs::new())
+        && from != dirs.home_dir()
+    {
+        archive::remap_home(&mut incoming, from, dirs.home_dir());
+        println!("Remapped paths from {:?} to {:?}.", from, dirs.home_dir());
+    }
+
+    let report = Database::update(|db| Ok(archive::merge(db, incoming)))?;
+    println!(
+        "Imported {} notes ({} already present), {} summaries, {} repositories and {} scan roots.",
+        report.notes_added,
+        report.notes_skipped,
+        report.summaries_added,
+        report.repos_added,
+        report.roots_added
+    );
+    for (archived, local) in &report.notes_renumbered {
+        println!(
+            "{}",
+            format!("Note #{} from the archive clashed with a local note and was added as #{}.", archived, local)
+                .yellow()
+        );
+    }
+
+    match archive.settings {
+        Some(_) if !with_settings => {
+            println!("The archive also holds settings. Pass --settings to replace your local config with them.");
+        }
+        None if with_settings => println!("The archive holds no settings."),
+        None => {}
+        Some(settings) => {
+            let current = settings::load_file()?;
+            let imported: settings::AppConfig =
+                serde_json::from_value(settings).context("The archived settings are invalid.")?;
+            let imported = settings::fill_missing_secrets(imported, &current)?;
+            settings::validate(&imported).context("The archived settings are invalid.")?;
+            settings::store(&imported)?;
+            println!("Settings imported.");
+        }
+    }
+    Ok(())
 }
 
-pub fn list() -> Result<()> {
-    let db = Database::load()?;
-    println!("Tracked repos:");
-    for repo in db.repositories {
-        println!(" - {:?}", repo);
+pub fn team(summarize: bool, no_cache: bool, args: &Args) -> Result<()> {
+    rescan_roots(false)?;
+    let db = Database::load_index()?;
+    let app_config = settings::load()?;
+    let since_utc = get_since_date(args.days);
+
+    let repos = db.active_repos(args.group.as_deref());
+    let (commits, failures) = collect_commits(&repos, args, |path| {
+        git_ops::fetch_all_commits(path, since_utc)
+    });
+    check_failures(&failures, args)?;
+    print_failures(&failures, args);
+
+    let commits = commits.into_iter().map(|(repo, c)| (repo.name.clone(), c)).collect();
+    let mut members = team::group_by_author(&app_config.team.members, commits);
+    if let Some(filter) = &args.author {
+        let filter = filter.to_lowercase();
+        members.retain(|m| m.name.to_lowercase().contains(&filter));
+    }
+
+    let policy = if no_cache {
+        CachePolicy::Bypass
+    } else {
+        CachePolicy::Use
+    };
+
+    let summaries: Vec<Option<String>> = members
+        .iter()
+        .map(|member| {
+            if !summarize || member.commits.is_empty() {
+                return None;
+            }
+            if args.format == OutputFormat::Table {
+                println!("AI summarizing {}... ({})", member.name, app_config.ai.model);
+            }
+
+            let mut logs = String::new();
+            for (repo, c) in &member.commits {
+                logs.push_str(&format!("Project: {} - {}\n", repo, c.message.trim()));
+            }
+            let template = app_config.team.prompt.replace("{NAME}", &member.name);
+
+            match ai::generate_with_prompt(&app_config, &template, &logs, policy) {
+                Ok(summary) => Some(summary),
+                Err(e) => {
+                    eprintln!("Failed to summarize {}: {}", member.name, e);
+                    None
+                }
+            }
+        })
+        .collect();
+
+    if args.format == OutputFormat::Json {
+        let members: Vec<JsonMember> = members
+            .iter()
+            .zip(summaries)
+            .map(|(member, summary)| JsonMember {
+                name: member.name.clone(),
+                commits: member
+                    .commits
+                    .iter()
+                    .map(|(repo, c)| commit_to_json(c, repo))
+                    .collect(),
+                summary,
+            })
+            .collect();
+        let output = JsonTeamReport {
+            since: since_utc.with_timezone(&Local),
+            members,
+            errors: failures.iter().map(RepoFailure::to_json).collect(),
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if args.format == OutputFormat::Csv {
+        println!("{}", csv_line(&["member", "date", "repo", "message", "hash"]));
+        for member in &members {
+            for (repo, c) in &member.commits {
+                println!(
+                    "{}",
+                    csv_line(&[&member.name, &c.date.to_rfc3339(), repo, &c.message, &c.hash])
+                );
+            }
+        }
+        return Ok(());
+    }
+
+    println!(
+        "Team report since {}",
+        since_utc.with_timezone(&Local).format("%d/%m %H:%M")
+    );
+    if members.is_empty() {
+        println!("No events for this time.");
+        return Ok(());
+    }
+
+    for (member, summary) in members.into_iter().zip(summaries) {
+        println!(
+            "\n{} ({} commits)",
+            member.name.bold().cyan(),
+            member.commits.len()
+        );
+        if member.commits.is_empty() {
+            println!("No commits.");
+            continue;
+        }
+
+        let rows: Vec<TeamRow> = member
+            .commits
+            .into_iter()
+            .map(|(repo, c)| TeamRow {
+                time: c.date.format("%d/%m %H:%M").to_string(),
+                repo,
+                message: c.message,
+                hash: c.hash,
+            })
+            .collect();
+        println!("{}", Table::new(rows));
+
+        if let Some(summary) = summary {
+            println!("{}", summary);
+        }
     }
     Ok(())
 }
 
-pub fn note(message: String) -> Result<()> {
-    let mut db = Database::load()?;
-    db.add_entry(message);
-    db.save()?;
-    println!("Note saved. It will appear in your next daily report.");
+pub fn hours(max_gap: Option<u64>, first_commit: Option<u64>, args: &Args) -> Result<()> {
+    rescan_roots(false)?;
+    let db = Database::load_index()?;
+    let app_config = settings::load()?;
+    let since_utc = get_since_date(args.days);
+
+    let max_gap = Duration::minutes(max_gap.unwrap_or(app_config.hours.max_gap_minutes) as i64);
+    let first_commit =
+        Duration::minutes(first_commit.unwrap_or(app_config.hours.first_commit_minutes) as i64);
+
+    let repos = db.active_repos(args.group.as_deref());
+    let authors = author_filters(args)?;
+    let (commits, failures) = collect_commits(&repos, args, |path| {
+        git_ops::fetch_commits(path, since_utc, &authors)
+    });
+    check_failures(&failures, args)?;
+    if args.format != OutputFormat::Json {
+        print_failures(&failures, args);
+    }
+    let mut events: Vec<hours::WorkEvent> = commits
+        .into_iter()
+    .map(|(repo, c)| hours::WorkEvent {
+        date: c.date,
+        repo: repo.name.clone(),
+        ticket: c.tickets.first().cloned(),
+    })
+    .collect();
+
+    for entry in Database::notes_between(Some(since_utc), None)? {
+        if entry.date > since_utc && note_matches(&entry, args) && note_in_repos(&entry, &repos, args) {
+            events.push(hours::WorkEvent {
+                date: DateTime::from(entry.date),
+                repo: entry.repo.clone().unwrap_or_else(|| "(notes)".to_string()),
+                ticket: conventional::ticket_keys(&entry.message).into_iter().next(),
+            });
+        }
+    }
+
+    let entries = hours::estimate(events, max_gap, first_commit);
+    let total: i64 = entries.iter().map(|e| e.minutes).sum();
+    let by_repo = hours::total_by(&entries, |e| e.repo.clone());
+    let by_ticket = hours::total_by(&entries, |e| {
+        e.ticket.clone().unwrap_or_else(|| "(no ticket)".to_string())
+    });
+    let by_day = hours::total_by(&entries, |e| e.day.to_string());
+
+    match args.format {
+        OutputFormat::Json => {
+            let as_hours = |totals: BTreeMap<String, i64>| -> BTreeMap<String, f64> {
+                totals
+                    .into_iter()
+                    .map(|(k, minutes)| (k, minutes as f64 / 60.0))
+                    .collect()
+            };
+            let output = JsonHours {
+                since: since_utc.with_timezone(&Local),
+                total_hours: total as f64 / 60.0,
+                by_repo: as_hours(by_repo),
+                by_ticket: as_hours(by_ticket),
+                by_day: as_hours(by_day),
+                entries: entries
+                    .into_iter()
+                    .map(|e| JsonTimeEntry {
+                        day: e.day.to_string(),
+                        repo: e.repo,
+                        ticket: e.ticket,
+                        hours: e.minutes as f64 / 60.0,
+                    })
+                    .collect(),
+                errors: failures.iter().map(RepoFailure::to_json).collect(),
+            };
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        OutputFormat::Csv => {
+            println!("{}", csv_line(&["day", "repo", "ticket", "hours"]));
+            for e in entries {
+                println!(
+                    "{}",
+                    csv_line(&[
+                        &e.day.to_string(),
+                        &e.repo,
+                        e.ticket.as_deref().unwrap_or(""),
+                        &hours::format_hours(e.minutes),
+                    ])
+                );
+            }
+        }
+        OutputFormat::Html => bail!("--format html is only supported for the report and check"),
+        OutputFormat::Table => {
+            println!(
+                "Estimated hours since {}",
+                since_utc.with_timezone(&Local).format("%d/%m %H:%M")
+            );
+            if entries.is_empty() {
+                println!("No events for this time.");
+                return Ok(());
+            }
+
+            for (title, totals) in [("Repo", by_repo), ("Ticket", by_ticket), ("Day", by_day)] {
+                let mut builder = Builder::default();
+                builder.push_record([title, "Hours"]);
+                for (key, minutes) in totals {
+                    builder.push_record([key, hours::format_hours(minutes)]);
+                }
+                println!("{}", builder.build());
+            }
+            println!("Total: {} h", hours::format_hours(total).bold());
+        }
+    }
     Ok(())
 }
 
-pub fn config(
-    set_key: Option<String>,
-    set_url: Option<String>,
-    set_model: Option<String>,
-    set_prompt: Option<String>,
-    set_lang: Option<String>,
-) -> Result<()> {
-    let cfg_name = "yestergit";
-    let mut app_config: settings::AppConfig = confy::load(cfg_name, "config")?;
-    let mut changed = false;
+pub fn stats(weeks: u32, top: usize, args: &Args) -> Result<()> {
+    rescan_roots(false)?;
+    let db = Database::load_index()?;
+    let today = Local::now().date_naive();
+    let weeks = match args.days {
+        Some(_) => {
+            let since_day = get_since_date(args.days).with_timezone(&Local).date_naive();
+            stats::weeks_between(since_day, today)
+        }
+        None => weeks,
+    };
+    let heatmap_start = stats::heatmap_start(today, weeks);
+    let since_utc = match args.days {
+        Some(_) => get_since_date(args.days),
+        None => heatmap_start
+            .and_time(NaiveTime::from_hms_opt(0, 0, 0).unwrap())
+            .and_local_timezone(Local)
+            .unwrap()
+            .with_timezone(&Utc),
+    };
+
+    let repos = db.active_repos(args.group.as_deref());
+    let authors = author_filters(args)?;
+    let (commits, failures) = collect_commits(&repos, args, |path| {
+        git_ops::fetch_commits_with_stats(path, since_utc, &authors)
+    });
+    check_failures(&failures, args)?;
+    if args.format != OutputFormat::Json {
+        print_failures(&failures, args);
+    }
 
-    if let Some(v) = set_url {
-        app_config.ai.api_url = v;
-        println!("API URL updated.");
-        changed = true;
+    let commits: Vec<(String, git_ops::CommitLog)> =
+        commits.into_iter().map(|(repo, c)| (repo.name.clone(), c)).collect();
+    let activity = stats::compute(&commits, top);
+
+    match args.format {
+        OutputFormat::Json => {
+            let output = JsonStats {
+                activity: &activity,
+                errors: failures.iter().map(RepoFailure::to_json).collect(),
+            };
+            println!("{}", serde_json::to_string_pretty(&output)?);
+            return Ok(());
+        }
+        OutputFormat::Csv => {
+            println!("{}", csv_line(&["section", "key", "value"]));
+            for (section, key, value) in stats::csv_rows(&activity) {
+                println!("{}", csv_line(&[section, &key, &value.to_string()]));
+            }
+            return Ok(());
+        }
+        OutputFormat::Table => {}
+        OutputFormat::Html => bail!("--format html is only supported for the report and check"),
     }
 
-    if let Some(v) = set_key {
-        app_config.ai.api_key = v;
-        println!("API Key updated.");
-        changed = true;
+    println!(
+        "Activity since {}",
+        since_utc.with_timezone(&Local).format("%d/%m/%Y")
+    );
+    if activity.total_commits == 0 {
+        println!("No commits for this time.");
+        return Ok(());
     }
 
-    if let Some(v) = set_model {
-        app_config.ai.model = v;
-        println!("AI Model updated.");
-        changed = true;
+    println!(
+        "{} commits, {} / {} lines\n",
+        activity.total_commits.to_string().bold(),
+        format!("+{}", activity.insertions).green(),
+        format!("-{}", activity.deletions).red()
+    );
+
+    println!("{}", "Commits per repo".bold());
+    println!(
+        "{}",
+        stats::render_bars(activity.per_repo.iter().map(|(repo, count)| (repo.as_str(), *count)))
+    );
+
+    println!("{}", "Commits per weekday".bold());
+    println!("{}", stats::render_bars(stats::weekday_rows(&activity)));
+
+    println!("{}", "Commits per hour".bold());
+    let hour_labels: Vec<String> = (0..24).map(|h| format!("{:02}", h)).collect();
+    println!(
+        "{}",
+        stats::render_bars(
+            hour_labels
+                .iter()
+                .map(String::as_str)
+                .zip(activity.per_hour)
+                .filter(|(_, count)| *count > 0)
+        )
+    );
+
+    if !activity.top_files.is_empty() {
+        println!("{}", "Most touched files".bold());
+        let mut builder = Builder::default();
+        builder.push_record(["File", "Commits"]);
+        for (file, count) in &activity.top_files {
+            builder.push_record([file.clone(), count.to_string()]);
+        }
+        println!("{}\n", builder.build());
     }
 
-    if let Some(v) = set_prompt {
-        app_config.ai.prompt = v;
-        println!("Prompt changed.");
-        changed = true;
+    println!("{}", format!("Since {}", heatmap_start.format("%d/%m/%Y")).bold());
+    println!(
+        "{}",
+        stats::render_heatmap(&activity.per_day, heatmap_start, today)
+    );
+    Ok(())
+}
+
+fn hook_targets(target: Option<String>, all: bool) -> Result<Vec<PathBuf>> {
+    let db = Database::load_index()?;
+    if all {
+        let repos: Vec<PathBuf> = db.repositories.clone();
+        if repos.is_empty() {
+            bail!("No repositories are tracked yet. Run `yestergit scan` first.");
+        }
+        return Ok(repos);
     }
 
-    if let Some(v) = set_lang {
-        app_config.ai.language = v;
-        println!("Language changed.");
-        changed = true;
+    let target = match target {
+        Some(target) => target,
+        None => {
+            let repo = git2::Repository::discover(".")
+                .context("Not inside a git repository. Pass a tracked repository or --all.")?;
+            hooks::repo_root(&repo).to_string_lossy().to_string()
+        }
+    };
+    match db.find_repos(&target).as_slice() {
+        [] => bail!(
+            "No tracked repository matches {:?}. Track it with `yestergit scan --path <dir>` first.",
+            target
+        ),
+        [path] => Ok(vec![path.clone()]),
+        _ => bail!("{:?} matches several repositories, pass a full path instead.", target),
     }
+}
 
-    if changed {
-        confy::store(cfg_name, "config", &app_config)?;
-        println!("Settings saved.");
-    } else {
-        let path = confy::get_configuration_file_path(cfg_name, "config")?;
-        println!("Config file: {:?}", path);
-        if let Ok(db_path) = Database::get_path() {
-             println!("Database file: {:?}", db_path);
+fn for_each_hook_target(
+    target: Option<String>,
+    all: bool,
+    f: impl Fn(&Path) -> Result<String>,
+) -> Result<()> {
+    let mut failed = 0;
+    for path in hook_targets(target, all)? {
+        match f(&path) {
+            Ok(message) => println!("{}", message),
+            Err(e) => {
+                eprintln!("{} {:?}: {:#}", "Skipped".yellow(), path, e);
+                failed += 1;
+            }
         }
-        println!("{:#?}", app_config);
+    }
+    if failed > 0 {
+        bail!("{} repositories could not be updated.", failed);
     }
     Ok(())
 }
 
-pub fn summarize(args: &Args) -> Result<()> {
-    let db = Database::load()?;
-    let cfg_name = "yestergit";
-    let app_config: settings::AppConfig = confy::load(cfg_name, "config")?;
+pub fn hooks_install(target: Option<String>, all: bool) -> Result<()> {
+    let exe = std::env::current_exe().context("Could not find the yestergit executable.")?;
+    for_each_hook_target(target, all, |path| {
+        let chained = hooks::install(path, &exe, profile::active())?;
+        Ok(if chained.is_empty() {
+            format!("Installed hooks in {:?}.", path)
+        } else {
+            format!(
+                "Installed hooks in {:?}, the existing {} hook still runs first.",
+                path,
+                chained.join(" and ")
+            )
+        })
+    })
+}
 
-    let logs = collect_logs_as_string(db.repositories, db.entries, args)?;
-    if logs.trim().is_empty() {
-        println!("There are no logs.");
+pub fn hooks_uninstall(target: Option<String>, all: bool) -> Result<()> {
+    for_each_hook_target(target, all, |path| {
+        Ok(if hooks::uninstall(path)? {
+            format!("Removed hooks from {:?}.", path)
+        } else {
+            format!("No yestergit hooks in {:?}.", path)
+        })
+    })
+}
+
+pub fn hooks_record(event: &str, head: &str, branch: &str, hook_args: &[String]) -> Result<()> {
+    let repo = git2::Repository::open_from_env().context("Not inside a git repository.")?;
+    let Some(message) = hooks::activity(&repo, event, head, branch, hook_args)? else {
         return Ok(());
+    };
+
+    let mut candidates: Vec<PathBuf> = repo.workdir().map(Path::to_path_buf).into_iter().collect();
+    candidates.push(hooks::repo_root(&repo));
+    let candidates: Vec<PathBuf> = candidates
+        .into_iter()
+        .map(|p| std::fs::canonicalize(&p).unwrap_or(p))
+        .collect();
+
+    Database::update(|db| {
+        let name = db
+            .tracked_repos()
+            .into_iter()
+            .find(|r| candidates.contains(&r.path))
+            .map(|r| r.name)
+            .unwrap_or_else(|| repo_name(&candidates[0]));
+        db.add_activity(message, Utc::now(), name);
+        Ok(())
+    })
+}
+
+pub fn doctor(args: &Args) -> Result<()> {
+    let since = get_since_date(args.days);
+    let config_path = settings::config_path()?;
+    let db_path = Database::get_path()?;
+
+    let mut setup = vec![doctor::check_config_file(&config_path, settings::load_file())];
+    let app_config = match settings::load() {
+        Ok(config) => config,
+        Err(e) => {
+            setup.push(doctor::Check::fail(
+                "Environment",
+                format!("{:#}", e),
+                "Fix or unset the YESTERGIT_* variable.",
+            ));
+            settings::AppConfig::default()
+        }
+    };
+    setup.extend(doctor::check_database(&db_path, || {
+        Ok(Database::load_index()?.repositories.len())
+    }));
+    setup.push(doctor::check_window(since));
+
+    let authors = author_filters(args).unwrap_or_default();
+    let repos = Database::load_index()
+        .map(|db| db.active_repos(args.group.as_deref()))
+        .unwrap_or_default();
+    let repo_checks: Vec<doctor::Check> = repos
+        .par_iter()
+        .map(|repo| doctor::check_repo(&repo.path, since, &authors))
+        .collect();
+
+    let sections = [
+        ("Setup", setup),
+        ("Repositories", repo_checks),
+        ("AI", doctor::check_ai(&app_config.ai)),
+    ];
+
+    let mut failures = 0;
+    let mut warnings = 0;
+    for (title, checks) in &sections {
+        if checks.is_empty() {
+            continue;
+        }
+        println!("{}", title.bold());
+        for check in checks {
+            let mark = match check.status {
+                doctor::Status::Ok => "✔".green(),
+                doctor::Status::Warn => {
+                    warnings += 1;
+                    "!".yellow()
+                }
+                doctor::Status::Fail => {
+                    failures += 1;
+                    "✘".red()
+                }
+            };
+            println!("  {} {}: {}", mark, check.label, check.detail);
+            if let Some(hint) = &check.hint {
+                println!("      {}", hint.dimmed());
+            }
+        }
+        println!();
     }
 
-    println!("AI generating summary... ({})", app_config.ai.model);
+    if failures > 0 {
+        bail!("{} check(s) failed, {} warning(s).", failures, warnings);
+    }
+    println!("All checks passed, {} warning(s).", warnings);
+    Ok(())
+}
 
-    match ai::generate_summary(&app_config, logs) {
-        Ok(summary) => {
-            println!("\n{}", "--- Daily Report ---".bold().green());
-            println!("{}", summary);
-            println!("{}", "-----------------".green());
+pub fn team_add(name: String, aliases: Vec<String>) -> Result<()> {
+    let mut app_config = settings::load_file()?;
+
+    match app_config.team.members.iter_mut().find(|m| m.name == name) {
+        Some(member) => {
+            for alias in aliases {
+                if !member.aliases.contains(&alias) {
+                    member.aliases.push(alias);
+                }
+            }
+            println!("Team member updated.");
+        }
+        None => {
+            app_config
+                .team
+                .members
+                .push(settings::TeamMember { name, aliases });
+            println!("Team member added.");
+        }
+    }
+
+    settings::store(&app_config)?;
+    Ok(())
+}
+
+pub fn team_remove(name: String) -> Result<()> {
+    let mut app_config = settings::load_file()?;
+
+    let before = app_config.team.members.len();
+    app_config.team.members.retain(|m| m.name != name);
+    if app_config.team.members.len() == before {
+        bail!("No team member named {:?}.", name);
+    }
+
+    settings::store(&app_config)?;
+    println!("Team member removed.");
+    Ok(())
+}
+
+pub fn team_members() -> Result<()> {
+    let app_config = settings::load()?;
+
+    println!("Team members:");
+    for member in app_config.team.members {
+        if member.aliases.is_empty() {
+            println!(" - {}", member.name);
+        } else {
+            println!(" - {} ({})", member.name, member.aliases.join(", "));
         }
-        Err(e) => eprintln!("Failed to generate report: {}", e),
     }
     Ok(())
 }
 
 pub fn report_all(args: &Args) -> Result<()> {
-    let db = Database::load()?;
-    print_report(db.repositories, db.entries, args)
+    rescan_roots(false)?;
+    let db = Database::load_index()?;
+    let repos = db.active_repos(args.group.as_deref());
+    let notes = Database::notes_between(Some(get_since_date(args.days)), None)?;
+    print_report(repos, notes, args)
+}
+
+fn author_filters(args: &Args) -> Result<Vec<String>> {
+    match &args.author {
+        Some(author) => Ok(vec![author.clone()]),
+        None => Ok(settings::load()?.identity.authors),
+    }
 }
 
 fn get_since_date(days_arg: Option<u64>) -> DateTime<Utc> {
@@ -182,53 +1868,196 @@ fn get_since_date(days_arg: Option<u64>) -> DateTime<Utc> {
     since_local.with_timezone(&Utc)
 }
 
-fn print_report(repos: Vec<PathBuf>, entries: Vec<ManuelEntry>, args: &Args) -> Result<()> {
+fn collect_timeline(
+    repos: &[TrackedRepo],
+    entries: Vec<ManuelEntry>,
+    args: &Args,
+    with_stats: bool,
+) -> Result<(Vec<TimelineEvent>, Vec<RepoFailure>)> {
     let since_utc = get_since_date(args.days);
-    println!(
-        "Reports since {}",
-        since_utc.with_timezone(&Local).format("%d/%m %H:%M")
-    );
-
-    let mut all_events: Vec<TimelineEvent> = repos
-        .par_iter()
-        .map(
-            |repo_path| match git_ops::fetch_commits(repo_path, since_utc, args.author.clone()) {
-                Ok(logs) => {
-                    let repo_name = std::fs::canonicalize(repo_path)
-                        .ok()
-                        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
-                        .unwrap_or_else(|| repo_path.to_string_lossy().to_string());
-
-                    logs.into_iter()
-                        .map(|c| TimelineEvent::Commit(c, repo_name.clone()))
-                        .collect()
-                }
-                Err(_) => Vec::new(),
-            },
-        )
-        .flatten()
+    let authors = author_filters(args)?;
+    let (commits, failures) = collect_commits(repos, args, |path| {
+        if with_stats {
+            git_ops::fetch_commits_with_stats(path, since_utc, &authors)
+        } else {
+            git_ops::fetch_commits(path, since_utc, &authors)
+        }
+    });
+    check_failures(&failures, args)?;
+    let hashes: HashSet<String> = commits.iter().map(|(_, c)| c.hash.clone()).collect();
+    let mut all_events: Vec<TimelineEvent> = commits
+        .into_iter()
+        .map(|(repo, c)| TimelineEvent::Commit(Box::new(c), repo.clone()))
         .collect();
 
     for entry in entries {
-        if entry.date > since_utc {
+        if entry.date > since_utc
+            && note_matches(&entry, args)
+            && note_in_repos(&entry, repos, args)
+            && !is_recorded_commit(&entry, &hashes)
+        {
             all_events.push(TimelineEvent::Note(entry));
         }
     }
 
     all_events.sort_by_key(|a| a.date());
+    Ok((all_events, failures))
+}
+
+fn summarize_events(
+    app_config: &settings::AppConfig,
+    repos: &[TrackedRepo],
+    events: &[TimelineEvent],
+    policy: CachePolicy,
+) -> Result<String> {
+    let commits: Vec<(&TrackedRepo, git_ops::CommitLog)> = events
+        .iter()
+        .filter_map(|e| match e {
+            TimelineEvent::Commit(c, repo) => Some((repo, (**c).clone())),
+            TimelineEvent::Note(_) => None,
+        })
+        .collect();
+    let notes: Vec<ManuelEntry> = events
+        .iter()
+        .filter_map(|e| match e {
+            TimelineEvent::Note(n) => Some(n.clone()),
+            TimelineEvent::Commit(..) => None,
+        })
+        .collect();
+    let logs = format_logs(repos, &commits, &notes);
+    eprintln!("AI generating summary... ({})", app_config.ai.model);
+    let messages = ai::initial_messages(app_config, &logs);
+    ai::chat(app_config, &messages, policy)
+}
+
+fn write_html_report(
+    repos: &[TrackedRepo],
+    events: Vec<TimelineEvent>,
+    failures: Vec<RepoFailure>,
+    since_utc: DateTime<Utc>,
+    args: &Args,
+) -> Result<()> {
+    let summary = if args.no_summary || events.is_empty() {
+        None
+    } else {
+        let app_config = settings::load()?;
+        let summary = summarize_events(&app_config, repos, &events, CachePolicy::Use)
+            .context("Failed to generate the summary. Use --no-summary to build the page without it.")?;
+        Some(SummaryRecord {
+            date: Utc::now(),
+            model: app_config.ai.model,
+            summary,
+        })
+    };
+
+    let mut commits = Vec::new();
+    let mut notes = Vec::new();
+    for event in events {
+        match event {
+            TimelineEvent::Commit(c, repo) => commits.push((repo.name, *c)),
+            TimelineEvent::Note(n) => notes.push(n),
+        }
+    }
+
+    let since = since_utc.with_timezone(&Local);
+    let page = html::render(&html::Page {
+        title: format!("Report since {}", since.format("%d/%m/%Y")),
+        since,
+        commits: &commits,
+        notes: &notes,
+        summary: summary.as_ref(),
+        warnings: failures
+            .iter()
+            .map(|f| format!("{}: {}", f.repo, f.error))
+            .collect(),
+    });
+
+    if !args.open {
+        print!("{}", page);
+        print_failures(&failures, args);
+        return Ok(());
+    }
+
+    let path = std::env::temp_dir().join(format!(
+        "yestergit-report-{}.html",
+        Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    std::fs::write(&path, page).with_context(|| format!("Could not write {:?}", path))?;
+    print_failures(&failures, args);
+    println!("{}", path.display());
+    Ok(())
+}
+
+fn print_report(repos: Vec<TrackedRepo>, entries: Vec<ManuelEntry>, args: &Args) -> Result<()> {
+    let since_utc = get_since_date(args.days);
+    if args.format == OutputFormat::Table && !args.open {
+        println!(
+            "Reports since {}",
+            since_utc.with_timezone(&Local).format("%d/%m %H:%M")
+        );
+    }
+
+    let html = args.open || args.format == OutputFormat::Html;
+    let (all_events, failures) = collect_timeline(&repos, entries, args, html)?;
+
+    if html {
+        return write_html_report(&repos, all_events, failures, since_utc, args);
+    }
+
+    if args.format == OutputFormat::Json {
+        let output = JsonReport {
+            since: since_utc.with_timezone(&Local),
+            events: all_events.iter().map(TimelineEvent::to_json).collect(),
+            errors: failures.iter().map(RepoFailure::to_json).collect(),
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if args.format == OutputFormat::Csv {
+        println!("{}", csv_line(&["date", "type", "source", "change", "ticket", "message", "hash"]));
+        for event in &all_events {
+            let line = match event {
+                TimelineEvent::Commit(c, repo) => csv_line(&[
+                    &c.date.to_rfc3339(),
+                    "commit",
+                    &repo.name,
+                    &change_label(c),
+                    &c.tickets.join(" "),
+                    &c.message,
+                    &c.hash,
+                ]),
+                TimelineEvent::Note(n) => csv_line(&[
+                    &DateTime::<Local>::from(n.date).to_rfc3339(),
+                    &n.kind.label().to_lowercase(),
+                    n.repo.as_deref().unwrap_or("-"),
+                    "-",
+                    &conventional::ticket_keys(&n.message).join(" "),
+                    &n.message,
+                    "-",
+                ]),
+            };
+            println!("{}", line);
+        }
+        print_failures(&failures, args);
+        return Ok(());
+    }
 
     if all_events.is_empty() {
         println!("No events for this time.");
+        print_failures(&failures, args);
         return Ok(());
     }
 
     let mut table_rows = Vec::new();
     for event in all_events {
         let row = match event {
-            TimelineEvent::Commit(c, repo_name) => ReportRow {
+            TimelineEvent::Commit(c, repo) => ReportRow {
                 time: c.date.format("%d/%m %H:%M").to_string(),
                 event_type: "Git".to_string(),
-                source: repo_name,
+                source: repo.name,
+                change: change_label(&c),
+                ticket: tickets_label(&c.tickets),
                 message: c.message.to_string(),
                 hash: c.hash,
             },
@@ -236,59 +2065,101 @@ fn print_report(repos: Vec<PathBuf>, entries: Vec<ManuelEntry>, args: &Args) ->
                 time: DateTime::<Local>::from(n.date)
                     .format("%d/%m %H:%M")
                     .to_string(),
-                event_type: "Note".to_string(),
-                source: "-".to_string(),
-                message: n.message,
-                hash: "-".to_string(),
+                event_type: n.kind.label().to_string(),
+                source: n.repo.clone().unwrap_or_else(|| "-".to_string()),
+                change: "-".to_string(),
+                ticket: tickets_label(&conventional::ticket_keys(&n.message)),
+                message: if n.tags.is_empty() {
+                    n.message
+                } else {
+                    format!("{} {}", n.message, tags_label(&n.tags))
+                },
+                hash: format!("#{}", n.id),
             },
         };
         table_rows.push(row);
     }
     println!("{}", Table::new(table_rows));
+    print_failures(&failures, args);
     Ok(())
 }
 
 fn collect_logs_as_string(
-    repos: Vec<PathBuf>,
+    repos: Vec<TrackedRepo>,
     entries: Vec<ManuelEntry>,
     args: &Args,
 ) -> Result<String> {
     let since_utc = get_since_date(args.days);
 
-    let repo_logs: Vec<String> = repos
-        .par_iter()
-        .map(|repo_path| {
-            let mut chunk = String::new();
-            if let Ok(commits) = git_ops::fetch_commits(repo_path, since_utc, args.author.clone())
-                && !commits.is_empty() {
-                    let repo_name = repo_path.file_name().unwrap_or_default().to_string_lossy();
-                    chunk.push_str(&format!("Project: {}\n", repo_name));
-
-                    for c in commits {
-                        chunk.push_str(&format!("- {}\n", c.message.trim()));
-                    }
-                    chunk.push('\n');
-                }
-            chunk
-        })
+    let authors = author_filters(args)?;
+    let (commits, failures) = collect_commits(&repos, args, |path| {
+        git_ops::fetch_commits(path, since_utc, &authors)
+    });
+    check_failures(&failures, args)?;
+    print_failures(&failures, args);
+
+    let hashes: HashSet<String> = commits.iter().map(|(_, c)| c.hash.clone()).collect();
+    let mut notes: Vec<ManuelEntry> = entries
+        .into_iter()
+        .filter(|e| e.date > since_utc && note_matches(e, args) && note_in_repos(e, &repos, args))
+        .filter(|e| !is_recorded_commit(e, &hashes))
         .collect();
+    notes.sort_by_key(|e| e.date);
+
+    Ok(format_logs(&repos, &commits, &notes))
+}
+
+fn format_logs(
+    repos: &[TrackedRepo],
+    commits: &[(&TrackedRepo, git_ops::CommitLog)],
+    notes: &[ManuelEntry],
+) -> String {
     let mut clean_logs = String::new();
-    for log in repo_logs {
-        clean_logs.push_str(&log);
+    for repo in repos {
+        let repo_commits: Vec<git_ops::CommitLog> = commits
+            .iter()
+            .filter(|(r, _)| r.path == repo.path)
+            .map(|(_, c)| c.clone())
+            .collect();
+        let repo_notes: Vec<&ManuelEntry> = notes
+            .iter()
+            .filter(|n| n.repo.as_deref().is_some_and(|r| repo_label_matches(r, repo)))
+            .collect();
+        if repo_commits.is_empty() && repo_notes.is_empty() {
+            continue;
+        }
+
+        clean_logs.push_str(&format!("Project: {}\n", repo.name));
+        clean_logs.push_str(&format_commit_groups(&repo_commits));
+        for note in repo_notes {
+            clean_logs.push_str(&format!("{}: {}\n", note.kind.label(), note.message.trim()));
+        }
+        clean_logs.push('\n');
     }
 
-    let notes: Vec<String> = entries
-        .into_iter()
-        .filter(|e| e.date > since_utc)
-        .map(|e| format!("Note: {}\n", e.message.trim()))
+    let loose_notes: Vec<&ManuelEntry> = notes
+        .iter()
+        .filter(|n| {
+            !n.repo
+                .as_deref()
+                .is_some_and(|r| repos.iter().any(|repo| repo_label_matches(r, repo)))
+        })
         .collect();
 
-    if !notes.is_empty() {
+    if !loose_notes.is_empty() {
         clean_logs.push_str("\n --- Manual Notes --\n");
-        for note in notes {
-            clean_logs.push_str(&note);
+        for note in loose_notes {
+            match &note.repo {
+                Some(repo) => clean_logs.push_str(&format!(
+                    "{} ({}): {}\n",
+                    note.kind.label(),
+                    repo,
+                    note.message.trim()
+                )),
+                None => clean_logs.push_str(&format!("{}: {}\n", note.kind.label(), note.message.trim())),
+            }
         }
     }
 
-    Ok(clean_logs)
+    clean_logs
 }
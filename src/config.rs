@@ -1,4 +1,6 @@
-use clap::{Parser, Subcommand};
+use crate::post::Sink;
+use crate::storage::Backend;
+use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
@@ -7,14 +9,46 @@ pub struct Args {
     #[command(subcommand)]
     pub command: Option<Commands>,
 
+    #[arg(long, global = true, env = "YESTERGIT_PROFILE")]
+    pub profile: Option<String>,
+
     #[arg(short, long, global = true)]
     pub author: Option<String>,
 
     #[arg(short, long, global = true)]
     pub days: Option<u64>,
 
+    #[arg(long = "type", global = true)]
+    pub commit_type: Option<String>,
+
+    #[arg(long, global = true)]
+    pub ticket: Option<String>,
+
+    #[arg(short, long, global = true)]
+    pub group: Option<String>,
+
     #[arg(long, default_value_t = false, global = true)]
     pub verbose: bool,
+
+    #[arg(long, default_value_t = false, global = true)]
+    pub strict: bool,
+
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table, global = true)]
+    pub format: OutputFormat,
+
+    #[arg(long, default_value_t = false, global = true)]
+    pub open: bool,
+
+    #[arg(long, default_value_t = false, global = true)]
+    pub no_summary: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Csv,
+    Html,
 }
 
 #[derive(Subcommand, Debug)]
@@ -22,20 +56,138 @@ pub enum Commands {
     Scan {
         #[arg(short, long, default_value = ".")]
         path: PathBuf,
+        #[arg(long)]
+        max_depth: Option<usize>,
+        #[arg(long)]
+        follow_links: bool,
+        #[arg(long = "ignore")]
+        ignore: Vec<String>,
+        #[arg(long)]
+        submodules: bool,
     },
     Check {
         #[arg(short, long, default_value = ".")]
         path: PathBuf,
     },
-    List,
+    List {
+        #[arg(long)]
+        status: bool,
+    },
+
+    Untrack {
+        target: String,
+        #[arg(long)]
+        all: bool,
+    },
+
+    Repo {
+        target: String,
+        #[arg(long, conflicts_with = "clear_alias")]
+        set_alias: Option<String>,
+        #[arg(long)]
+        clear_alias: bool,
+        #[arg(long, conflicts_with = "clear_group")]
+        set_group: Option<String>,
+        #[arg(long)]
+        clear_group: bool,
+        #[arg(long, conflicts_with = "disable")]
+        enable: bool,
+        #[arg(long)]
+        disable: bool,
+    },
+
+    Prune {
+        #[arg(long)]
+        dry_run: bool,
+    },
 
+    Roots {
+        #[command(subcommand)]
+        action: RootsAction,
+    },
+
+    #[command(args_conflicts_with_subcommands = true)]
     Note {
-        message: String,
+        #[command(subcommand)]
+        action: Option<NoteAction>,
+        message: Option<String>,
     },
 
-    Summarize,
+    Summarize {
+        #[arg(long, conflicts_with = "regenerate")]
+        no_cache: bool,
+        #[arg(long)]
+        regenerate: bool,
+        #[arg(short, long)]
+        interactive: bool,
+        #[arg(long)]
+        save: bool,
+        #[arg(long)]
+        post: Vec<Sink>,
+        #[arg(short, long)]
+        yes: bool,
+    },
 
+    Email {
+        #[arg(long)]
+        to: Vec<String>,
+        #[arg(long)]
+        cc: Vec<String>,
+        #[arg(long)]
+        dry_run: bool,
+        #[arg(long)]
+        no_cache: bool,
+    },
+
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+
+    Db {
+        #[command(subcommand)]
+        action: DbAction,
+    },
+
+    Export {
+        file: PathBuf,
+        #[arg(long, alias = "include-api-key")]
+        include_secrets: bool,
+    },
+
+    Import {
+        file: PathBuf,
+        #[arg(long = "settings")]
+        with_settings: bool,
+    },
+
+    Hours {
+        #[arg(long)]
+        max_gap: Option<u64>,
+        #[arg(long)]
+        first_commit: Option<u64>,
+    },
+
+    Stats {
+        #[arg(short, long, default_value_t = 12)]
+        weeks: u32,
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+    },
+
+    Team {
+        #[command(subcommand)]
+        action: Option<TeamAction>,
+        #[arg(long)]
+        summarize: bool,
+        #[arg(long)]
+        no_cache: bool,
+    },
+
+    #[command(args_conflicts_with_subcommands = true)]
     Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
         #[arg(long)]
         set_key: Option<String>,
         #[arg(long)]
@@ -47,4 +199,114 @@ pub enum Commands {
         #[arg(long)]
         set_lang: Option<String>,
     },
+
+    Doctor,
+
+    Hooks {
+        #[command(subcommand)]
+        action: HooksAction,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    Get { key: Option<String> },
+    Set { key: String, value: String },
+    Unset { key: String },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RootsAction {
+    Add { path: PathBuf },
+    Rm { path: PathBuf },
+    List,
+    Scan,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DbAction {
+    Migrate {
+        #[arg(long, value_enum)]
+        to: Backend,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheAction {
+    Clear,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TeamAction {
+    Add {
+        name: String,
+        #[arg(long = "alias")]
+        aliases: Vec<String>,
+    },
+    Remove {
+        name: String,
+    },
+    Members,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HooksAction {
+    Install {
+        repo: Option<String>,
+        #[arg(long, conflicts_with = "repo")]
+        all: bool,
+    },
+    Uninstall {
+        repo: Option<String>,
+        #[arg(long, conflicts_with = "repo")]
+        all: bool,
+    },
+    #[command(hide = true)]
+    Record {
+        event: String,
+        #[arg(long)]
+        head: String,
+        #[arg(long, default_value = "")]
+        branch: String,
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum NoteAction {
+    Add {
+        message: String,
+        #[arg(long)]
+        at: Option<String>,
+        #[arg(short, long = "tag")]
+        tags: Vec<String>,
+        #[arg(long)]
+        repo: Option<String>,
+    },
+    List {
+        #[arg(long)]
+        tag: Option<String>,
+        #[arg(long)]
+        repo: Option<String>,
+        #[arg(long)]
+        activity: bool,
+    },
+    Edit {
+        id: u64,
+        message: Option<String>,
+        #[arg(long)]
+        at: Option<String>,
+        #[arg(short, long = "tag", conflicts_with = "clear_tags")]
+        tags: Vec<String>,
+        #[arg(long)]
+        clear_tags: bool,
+        #[arg(long, conflicts_with = "clear_repo")]
+        repo: Option<String>,
+        #[arg(long)]
+        clear_repo: bool,
+    },
+    Rm {
+        id: u64,
+    },
 }
@@ -0,0 +1,148 @@
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Conventional {
+    pub kind: Option<String>,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub tickets: Vec<String>,
+}
+
+const NOT_TICKETS: &[&str] = &["UTF", "SHA", "ISO", "CVE", "RFC", "HTTP"];
+
+pub fn parse(full_message: &str) -> Conventional {
+    let subject = full_message.lines().next().unwrap_or("");
+    let mut parsed = Conventional {
+        tickets: ticket_keys(full_message),
+        ..Default::default()
+    };
+
+    if let Some((kind, scope, bang)) = parse_header(subject) {
+        parsed.kind = Some(kind);
+        parsed.scope = scope;
+        parsed.breaking = bang;
+    }
+
+    if full_message.lines().skip(1).any(|line| {
+        line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:")
+    }) {
+        parsed.breaking = true;
+    }
+    parsed
+}
+
+fn parse_header(subject: &str) -> Option<(String, Option<String>, bool)> {
+    let (head, description) = subject.split_once(':')?;
+    if description.trim().is_empty() {
+        return None;
+    }
+
+    let (head, bang) = match head.strip_suffix('!') {
+        Some(h) => (h, true),
+        None => (head, false),
+    };
+
+    let (kind, scope) = match head.split_once('(') {
+        Some((kind, rest)) => {
+            let scope = rest.strip_suffix(')')?;
+            if scope.is_empty() || scope.contains(['(', ')']) {
+                return None;
+            }
+            (kind, Some(scope.to_string()))
+        }
+        None => (head, None),
+    };
+
+    if kind.is_empty() || !kind.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return None;
+    }
+    Some((kind.to_lowercase(), scope, bang))
+}
+
+pub fn ticket_keys(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut keys: Vec<String> = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let at_boundary = i == 0 || !chars[i - 1].is_ascii_alphanumeric();
+        if !at_boundary || !chars[i].is_ascii_uppercase() {
+            i += 1;
+            continue;
+        }
+
+        let mut j = i + 1;
+        while j < chars.len() && (chars[j].is_ascii_uppercase() || chars[j].is_ascii_digit()) {
+            j += 1;
+        }
+        if j - i < 2 || j >= chars.len() || chars[j] != '-' {
+            i = j;
+            continue;
+        }
+
+        let mut k = j + 1;
+        while k < chars.len() && chars[k].is_ascii_digit() {
+            k += 1;
+        }
+        let ends_cleanly = k == chars.len() || !chars[k].is_ascii_alphanumeric();
+        let project: String = chars[i..j].iter().collect();
+        if k > j + 1 && ends_cleanly && !NOT_TICKETS.contains(&project.as_str()) {
+            let key: String = chars[i..k].iter().collect();
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        i = k;
+    }
+    keys
+}
+
+pub fn kind_label(kind: Option<&str>) -> &str {
+    match kind {
+        Some("feat") => "Features",
+        Some("fix") => "Fixes",
+        Some("perf") => "Performance",
+        Some("refactor") => "Refactoring",
+        Some("docs") => "Documentation",
+        Some("test") => "Tests",
+        Some("build") | Some("ci") | Some("chore") => "Maintenance",
+        _ => "Other changes",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_conventional_header() {
+        let parsed = parse("feat(auth): add login for PROJ-123");
+        assert_eq!(parsed.kind.as_deref(), Some("feat"));
+        assert_eq!(parsed.scope.as_deref(), Some("auth"));
+        assert!(!parsed.breaking);
+        assert_eq!(parsed.tickets, vec!["PROJ-123"]);
+
+        let parsed = parse("refactor!: drop v1 api");
+        assert_eq!(parsed.kind.as_deref(), Some("refactor"));
+        assert_eq!(parsed.scope, None);
+        assert!(parsed.breaking);
+
+        let parsed = parse("fix: handle timeouts\n\nBREAKING CHANGE: retries are off\nRefs: OPS-7, PROJ-9");
+        assert!(parsed.breaking);
+        assert_eq!(parsed.tickets, vec!["OPS-7", "PROJ-9"]);
+    }
+
+    #[test]
+    fn test_parse_plain_message() {
+        let parsed = parse("Merge branch 'main': sync");
+        assert_eq!(parsed.kind, None);
+
+        let parsed = parse("Fixed the thing");
+        assert_eq!(parsed, Conventional::default());
+    }
+
+    #[test]
+    fn test_ticket_keys_boundaries() {
+        assert_eq!(ticket_keys("PROJ-1 and AB2-34."), vec!["PROJ-1", "AB2-34"]);
+        assert!(ticket_keys("xPROJ-1 P-1 PROJ-12a UTF-8 SHA-256 PROJ-").is_empty());
+        assert_eq!(ticket_keys("[PROJ-5] PROJ-5"), vec!["PROJ-5"]);
+    }
+}
@@ -0,0 +1,106 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone, Weekday};
+
+pub fn parse_when(input: &str, now: DateTime<Local>) -> Result<DateTime<Local>> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() || input == "now" {
+        return Ok(now);
+    }
+
+    if let Some(ago) = input.strip_suffix(" ago") {
+        return Ok(now - parse_duration(ago)?);
+    }
+
+    let mut parts = input.split_whitespace();
+    let first = parts.next().unwrap_or_default();
+    let second = parts.next();
+    if parts.next().is_some() {
+        bail!("Could not parse date {:?}.", input);
+    }
+
+    let (day, time) = match (parse_day(first, now.date_naive()), second) {
+        (Some(day), Some(time)) => (day, parse_time(time)?),
+        (Some(day), None) => (day, now.time()),
+        (None, None) => (now.date_naive(), parse_time(first)?),
+        (None, Some(_)) => bail!("Could not parse date {:?}.", input),
+    };
+
+    Local
+        .from_local_datetime(&day.and_time(time))
+        .earliest()
+        .with_context(|| format!("{:?} does not exist in the local timezone.", input))
+}
+
+fn parse_day(word: &str, today: NaiveDate) -> Option<NaiveDate> {
+    match word {
+        "today" => return Some(today),
+        "yesterday" => return Some(today - Duration::days(1)),
+        _ => {}
+    }
+
+    if let Ok(day) = NaiveDate::parse_from_str(word, "%Y-%m-%d") {
+        return Some(day);
+    }
+
+    let weekday: Weekday = word.parse().ok()?;
+    let mut back = (7 + today.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+    if back == 0 {
+        back = 7;
+    }
+    Some(today - Duration::days(back as i64))
+}
+
+fn parse_time(word: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(word, "%H:%M")
+        .with_context(|| format!("Could not parse time {:?}, expected HH:MM.", word))
+}
+
+fn parse_duration(input: &str) -> Result<Duration> {
+    let compact: String = input.split_whitespace().collect();
+    let split = compact
+        .find(|c: char| !c.is_ascii_digit())
+        .with_context(|| format!("Missing unit in {:?}.", input))?;
+    let (amount, unit) = compact.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .with_context(|| format!("Could not parse amount in {:?}.", input))?;
+
+    match unit {
+        "m" | "min" | "mins" | "minute" | "minutes" => Ok(Duration::minutes(amount)),
+        "h" | "hour" | "hours" => Ok(Duration::hours(amount)),
+        "d" | "day" | "days" => Ok(Duration::days(amount)),
+        _ => bail!("Unknown unit {:?}, use minutes, hours or days.", unit),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 3, 4, 18, 30, 0).unwrap()
+    }
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 3, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_relative_days() {
+        assert_eq!(parse_when("yesterday 16:00", now()).unwrap(), at(3, 16, 0));
+        assert_eq!(parse_when("today 09:15", now()).unwrap(), at(4, 9, 15));
+        assert_eq!(parse_when("Yesterday", now()).unwrap(), at(3, 18, 30));
+        assert_eq!(parse_when("8:05", now()).unwrap(), at(4, 8, 5));
+        assert_eq!(parse_when("monday 10:00", now()).unwrap(), at(2, 10, 0));
+        assert_eq!(parse_when("wednesday 10:00", now()).unwrap(), Local.with_ymd_and_hms(2026, 2, 25, 10, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_parse_absolute_and_ago() {
+        assert_eq!(parse_when("2026-03-01 12:00", now()).unwrap(), Local.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap());
+        assert_eq!(parse_when("2h ago", now()).unwrap(), at(4, 16, 30));
+        assert_eq!(parse_when("3 days ago", now()).unwrap(), at(1, 18, 30));
+        assert!(parse_when("next year", now()).is_err());
+        assert!(parse_when("25:00", now()).is_err());
+    }
+}
@@ -1,42 +1,218 @@
+use crate::{migrations, profile};
+use crate::storage::{self, Backend};
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteKind {
+    #[default]
+    Manual,
+    Activity,
+}
+
+impl NoteKind {
+    pub fn is_manual(&self) -> bool {
+        *self == NoteKind::Manual
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            NoteKind::Manual => "Note",
+            NoteKind::Activity => "Activity",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ManuelEntry {
+    #[serde(default)]
+    pub id: u64,
     pub message: String,
     pub date: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    #[serde(default, skip_serializing_if = "NoteKind::is_manual")]
+    pub kind: NoteKind,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SummaryRecord {
+    pub date: DateTime<Utc>,
+    pub model: String,
+    pub summary: String,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct RepoMeta {
+    pub alias: Option<String>,
+    pub group: Option<String>,
+    pub enabled: bool,
+}
+
+impl Default for RepoMeta {
+    fn default() -> Self {
+        Self {
+            alias: None,
+            group: None,
+            enabled: true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScanRoot {
+    pub path: PathBuf,
+    #[serde(default)]
+    pub last_scan: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub mtimes: BTreeMap<PathBuf, i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TrackedRepo {
+    pub path: PathBuf,
+    pub name: String,
+    pub meta: RepoMeta,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Database {
+    #[serde(default)]
+    pub schema_version: u32,
     pub repositories: Vec<PathBuf>,
     #[serde(default)]
     pub entries: Vec<ManuelEntry>,
+    #[serde(default)]
+    pub history: Vec<SummaryRecord>,
+    #[serde(default)]
+    pub next_note_id: u64,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub repo_meta: BTreeMap<PathBuf, RepoMeta>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roots: Vec<ScanRoot>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub untracked: Vec<PathBuf>,
+}
+
+impl Default for Database {
+    fn default() -> Self {
+        Self {
+            schema_version: migrations::CURRENT_VERSION,
+            repositories: Vec::new(),
+            entries: Vec::new(),
+            history: Vec::new(),
+            next_note_id: 0,
+            repo_meta: BTreeMap::new(),
+            roots: Vec::new(),
+            untracked: Vec::new(),
+        }
+    }
 }
 
 impl Database {
     pub fn load() -> Result<Self> {
         let path = get_db_path()?;
+        storage::open(&path, storage::detect(&path)).load()
+    }
+
+    pub fn load_index() -> Result<Self> {
+        let path = get_db_path()?;
+        storage::open(&path, storage::detect(&path)).load_index()
+    }
+
+    pub fn notes_between(from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Result<Vec<ManuelEntry>> {
+        let path = get_db_path()?;
+        storage::open(&path, storage::detect(&path)).notes_between(from, to)
+    }
+
+    pub fn history_between(
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<SummaryRecord>> {
+        let path = get_db_path()?;
+        storage::open(&path, storage::detect(&path)).history_between(from, to)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).context("Db can not be read.")?;
+        let mut value: serde_json::Value = serde_json::from_str(&content).context("Json error")?;
+        migrations::migrate(&mut value)?;
+        serde_json::from_value(value).context("Json error")
+    }
 
+    fn migrate_file(path: &Path) -> Result<()> {
         if !path.exists() {
-            return Ok(Database::default());
+            return Ok(());
         }
+        let content = fs::read_to_string(path).context("Db can not be read.")?;
+        let value: serde_json::Value = serde_json::from_str(&content).context("Json error")?;
+        let version = migrations::version_of(&value)?;
+        if version >= migrations::CURRENT_VERSION {
+            return Ok(());
+        }
+
+        let backup = migrations::backup_path(path, version);
+        fs::write(&backup, &content)
+            .with_context(|| format!("Failed to back up the database to {:?}", backup))?;
+        Self::load_from(path)?.save_to(&path.to_path_buf())
+    }
+
+    pub fn update<T>(f: impl FnOnce(&mut Database) -> Result<T>) -> Result<T> {
+        let path = get_db_path()?;
+        Self::update_at(&path, f)
+    }
 
-        let content = fs::read_to_string(&path).context("Db can not be read.")?;
-        let db: Database = serde_json::from_str(&content).context("Json error")?;
+    pub fn update_at<T>(path: &Path, f: impl FnOnce(&mut Database) -> Result<T>) -> Result<T> {
+        let _lock = lock(path)?;
 
-        Ok(db)
+        let backend = storage::detect(path);
+        if backend == Backend::Json {
+            Self::migrate_file(path)?;
+        }
+        let store = storage::open(path, backend);
+        let before = store.load()?;
+        let mut db = before.clone();
+        let result = f(&mut db)?;
+        store.save(&before, &db)?;
+        Ok(result)
     }
 
-    pub fn save(&self) -> Result<()> {
+    pub fn migrate_storage(to: Backend) -> Result<Option<PathBuf>> {
         let path = get_db_path()?;
-        self.save_to(&path)
+        let _lock = lock(&path)?;
+
+        let from = storage::detect(&path);
+        if from == to {
+            return Ok(None);
+        }
+        let source = storage::open(&path, from);
+        let target = storage::open(&path, to);
+
+        let db = source.load()?;
+        if target.path().exists() {
+            fs::remove_file(target.path())?;
+        }
+        target.save(&Database::default(), &db)?;
+
+        let mut backup = source.path().as_os_str().to_os_string();
+        backup.push(".migrated.bak");
+        let backup = PathBuf::from(backup);
+        if source.path().exists() {
+            fs::rename(source.path(), &backup)
+                .with_context(|| format!("Failed to move {:?} out of the way", source.path()))?;
+        }
+        Ok(Some(backup))
     }
 
     pub fn save_to(&self, path: &PathBuf) -> Result<()> {
@@ -74,26 +250,229 @@ impl Database {
         }
     }
 
-    pub fn add_entry(&mut self, message: String) {
+    pub fn find_repos(&self, target: &str) -> Vec<PathBuf> {
+        let as_path = PathBuf::from(target);
+        let canonical = fs::canonicalize(&as_path).ok();
+
+        let by_path: Vec<PathBuf> = self
+            .repositories
+            .iter()
+            .filter(|p| **p == as_path || Some(*p) == canonical.as_ref())
+            .cloned()
+            .collect();
+        if !by_path.is_empty() {
+            return by_path;
+        }
+
+        self.tracked_repos()
+            .into_iter()
+            .filter(|r| {
+                r.name == target
+                    || r.path.file_name().is_some_and(|n| n.to_string_lossy() == target)
+            })
+            .map(|r| r.path)
+            .collect()
+    }
+
+    pub fn untrack_repos(&mut self, paths: &[PathBuf]) {
+        self.remove_repos(paths);
+        for path in paths {
+            if self.roots.iter().any(|root| path.starts_with(&root.path)) && !self.untracked.contains(path) {
+                self.untracked.push(path.clone());
+            }
+        }
+    }
+
+    pub fn remove_repos(&mut self, paths: &[PathBuf]) {
+        self.repositories.retain(|p| !paths.contains(p));
+        self.repo_meta.retain(|p, _| !paths.contains(p));
+    }
+
+    pub fn add_root(&mut self, path: &Path) -> Result<bool> {
+        let path = fs::canonicalize(path).with_context(|| format!("{:?} does not exist.", path))?;
+        if self.roots.iter().any(|root| root.path == path) {
+            return Ok(false);
+        }
+        self.roots.push(ScanRoot {
+            path,
+            last_scan: None,
+            mtimes: BTreeMap::new(),
+        });
+        Ok(true)
+    }
+
+    pub fn remove_root(&mut self, path: &Path) -> bool {
+        let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let before = self.roots.len();
+        self.roots.retain(|root| root.path != path && root.path != canonical);
+        self.roots.len() != before
+    }
+
+    pub fn meta_mut(&mut self, path: &Path) -> &mut RepoMeta {
+        self.repo_meta.entry(path.to_path_buf()).or_default()
+    }
+
+    pub fn alias_owner(&self, path: &Path, alias: &str) -> Option<PathBuf> {
+        self.tracked_repos()
+            .into_iter()
+            .find(|r| {
+                r.path != path
+                    && (r.name.eq_ignore_ascii_case(alias) || basename(&r.path).eq_ignore_ascii_case(alias))
+            })
+            .map(|r| r.path)
+    }
+
+    pub fn tracked_repos(&self) -> Vec<TrackedRepo> {
+        let mut basename_counts: HashMap<String, usize> = HashMap::new();
+        for path in &self.repositories {
+            *basename_counts.entry(basename(path)).or_default() += 1;
+        }
+
+        self.repositories
+            .iter()
+            .map(|path| {
+                let meta = self.repo_meta.get(path).cloned().unwrap_or_default();
+                let base = basename(path);
+                let name = match &meta.alias {
+                    Some(alias) => alias.clone(),
+                    None if basename_counts[&base] > 1 => match path.parent() {
+                        Some(parent) => format!("{}/{}", basename(parent), base),
+                        None => base,
+                    },
+                    None => base,
+                };
+                TrackedRepo {
+                    path: path.clone(),
+                    name,
+                    meta,
+                }
+            })
+            .collect()
+    }
+
+    pub fn active_repos(&self, group: Option<&str>) -> Vec<TrackedRepo> {
+        self.tracked_repos()
+            .into_iter()
+            .filter(|r| r.meta.enabled)
+            .filter(|r| {
+                group.is_none_or(|g| {
+                    r.meta
+                        .group
+                        .as_deref()
+                        .is_some_and(|rg| rg.eq_ignore_ascii_case(g))
+                })
+            })
+            .collect()
+    }
+
+    pub fn add_entry(&mut self, message: String) -> u64 {
+        self.add_note(message, Utc::now(), Vec::new(), None)
+    }
+
+    pub fn add_note(
+        &mut self,
+        message: String,
+        date: DateTime<Utc>,
+        tags: Vec<String>,
+        repo: Option<String>,
+    ) -> u64 {
+        let id = self.next_note_id();
+        self.next_note_id = id + 1;
         self.entries.push(ManuelEntry {
+            id,
             message,
+            date,
+            tags,
+            repo,
+            kind: NoteKind::Manual,
+        });
+        id
+    }
+
+    pub fn add_activity(&mut self, message: String, date: DateTime<Utc>, repo: String) -> u64 {
+        let id = self.add_note(message, date, Vec::new(), Some(repo));
+        if let Some(entry) = self.find_entry_mut(id) {
+            entry.kind = NoteKind::Activity;
+        }
+        id
+    }
+
+    pub fn find_entry_mut(&mut self, id: u64) -> Option<&mut ManuelEntry> {
+        self.entries.iter_mut().find(|e| e.id == id)
+    }
+
+    pub fn remove_entry(&mut self, id: u64) -> Option<ManuelEntry> {
+        let index = self.entries.iter().position(|e| e.id == id)?;
+        Some(self.entries.remove(index))
+    }
+
+    fn next_note_id(&self) -> u64 {
+        let after_max = self.entries.iter().map(|e| e.id).max().unwrap_or(0) + 1;
+        self.next_note_id.max(after_max)
+    }
+
+    pub fn add_summary(&mut self, model: String, summary: String) {
+        self.history.push(SummaryRecord {
             date: Utc::now(),
+            model,
+            summary,
         });
     }
 
     pub fn get_path() -> Result<PathBuf> {
-        get_db_path()
+        let path = get_db_path()?;
+        Ok(storage::open(&path, storage::detect(&path)).path().to_path_buf())
+    }
+}
+
+fn basename(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string())
+}
+
+fn lock(db_path: &Path) -> Result<fs::File> {
+    if let Some(parent) = db_path.parent() {
+        fs::create_dir_all(parent)?;
     }
+
+    let mut name = db_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    let lock_path = db_path.with_file_name(name);
+
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .with_context(|| format!("Failed to open lock file {:?}", lock_path))?;
+    file.lock().context("Failed to lock the database")?;
+    Ok(file)
 }
 
 fn get_db_path() -> Result<PathBuf> {
-    if let Ok(env_path) = env::var("RECALL_DB_PATH") {
-        return Ok(PathBuf::from(env_path));
+    if let Ok(env_path) = env::var("YESTERGIT_DB_PATH").or_else(|_| env::var("RECALL_DB_PATH")) {
+        let path = PathBuf::from(env_path);
+        return Ok(match profile::active() {
+            Some(name) => {
+                let mut file = path.file_stem().unwrap_or_default().to_os_string();
+                file.push(format!("-{}", name));
+                if let Some(extension) = path.extension() {
+                    file.push(".");
+                    file.push(extension);
+                }
+                path.with_file_name(file)
+            }
+            None => path,
+        });
     }
     let proj_dirs = ProjectDirs::from("com", "yestergit-cli", "yestergit")
         .context("Config path does not exists.")?;
 
-    Ok(proj_dirs.config_dir().join("db.json"))
+    match profile::active() {
+        Some(name) => Ok(proj_dirs.config_dir().join("profiles").join(name).join("db.json")),
+        None => Ok(proj_dirs.config_dir().join("db.json")),
+    }
 }
 
 #[cfg(test)]
@@ -121,4 +500,92 @@ mod tests {
         let content_new = fs::read_to_string(&db_path).unwrap();
         assert!(content_new.contains("Second Note"));
     }
+
+    #[test]
+    fn test_find_and_remove_repos() {
+        let mut db = Database {
+            repositories: vec![
+                PathBuf::from("/work/api"),
+                PathBuf::from("/work/web"),
+                PathBuf::from("/old/api"),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(db.find_repos("/work/web"), vec![PathBuf::from("/work/web")]);
+        assert_eq!(db.find_repos("api").len(), 2);
+        assert!(db.find_repos("missing").is_empty());
+
+        let found = db.find_repos("/old/api");
+        db.remove_repos(&found);
+        assert_eq!(db.repositories.len(), 2);
+        assert_eq!(db.find_repos("api"), vec![PathBuf::from("/work/api")]);
+    }
+
+    #[test]
+    fn test_display_names_and_groups() {
+        let mut db = Database {
+            repositories: vec![
+                PathBuf::from("/work/backend"),
+                PathBuf::from("/oss/backend"),
+                PathBuf::from("/work/payments-api"),
+                PathBuf::from("/work/ledger"),
+            ],
+            ..Default::default()
+        };
+        db.meta_mut(&PathBuf::from("/work/payments-api")).alias = Some("Payments API".to_string());
+        db.meta_mut(&PathBuf::from("/work/payments-api")).group = Some("payments".to_string());
+        db.meta_mut(&PathBuf::from("/work/ledger")).group = Some("payments".to_string());
+        db.meta_mut(&PathBuf::from("/work/ledger")).enabled = false;
+
+        let names: Vec<String> = db.tracked_repos().into_iter().map(|r| r.name).collect();
+        assert_eq!(names, vec!["work/backend", "oss/backend", "Payments API", "ledger"]);
+
+        let payments: Vec<String> = db
+            .active_repos(Some("Payments"))
+            .into_iter()
+            .map(|r| r.name)
+            .collect();
+        assert_eq!(payments, vec!["Payments API"]);
+        assert_eq!(db.active_repos(None).len(), 3);
+        assert_eq!(db.find_repos("Payments API"), vec![PathBuf::from("/work/payments-api")]);
+
+        let payments_api = PathBuf::from("/work/payments-api");
+        assert_eq!(db.alias_owner(&payments_api, "payments api"), None);
+        assert_eq!(db.alias_owner(&payments_api, "Ledger"), Some(PathBuf::from("/work/ledger")));
+        assert_eq!(db.alias_owner(&payments_api, "oss/backend"), Some(PathBuf::from("/oss/backend")));
+        assert_eq!(db.alias_owner(&payments_api, "backend"), Some(PathBuf::from("/work/backend")));
+        assert_eq!(
+            db.alias_owner(&PathBuf::from("/work/ledger"), "Payments API"),
+            Some(payments_api)
+        );
+
+        db.remove_repos(&[PathBuf::from("/work/ledger")]);
+        assert!(!db.repo_meta.contains_key(&PathBuf::from("/work/ledger")));
+    }
+
+    #[test]
+    fn test_note_ids_are_stable() {
+        let mut value: serde_json::Value = serde_json::from_str(
+            r#"{"repositories": [], "entries": [
+                {"message": "old", "date": "2026-01-05T10:00:00Z"},
+                {"id": 4, "message": "newer", "date": "2026-01-06T10:00:00Z"}
+            ]}"#,
+        )
+        .unwrap();
+        migrations::migrate(&mut value).unwrap();
+        let mut db: Database = serde_json::from_value(value).unwrap();
+        assert_eq!(db.entries[0].id, 5);
+        assert_eq!(db.entries[1].id, 4);
+
+        let id = db.add_entry("third".to_string());
+        assert_eq!(id, 6);
+
+        db.find_entry_mut(4).unwrap().message = "edited".to_string();
+        assert_eq!(db.remove_entry(4).unwrap().message, "edited");
+        assert!(db.remove_entry(4).is_none());
+
+        db.remove_entry(6);
+        assert_eq!(db.add_entry("fourth".to_string()), 7);
+    }
 }
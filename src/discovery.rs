@@ -0,0 +1,260 @@
+use crate::db::{Database, ScanRoot};
+use crate::scanner::{self, ScanOptions};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use git2::Repository;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+const WATCH_DEPTH: usize = 2;
+
+#[derive(Debug, Default)]
+pub struct RescanReport {
+    pub checked: Vec<PathBuf>,
+    pub scanned: Vec<PathBuf>,
+    pub added: Vec<PathBuf>,
+    pub missing: Vec<PathBuf>,
+}
+
+fn dir_mtime(path: &Path) -> Option<i64> {
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    let since_epoch = modified.duration_since(UNIX_EPOCH).ok()?;
+    Some(since_epoch.as_nanos() as i64)
+}
+
+fn snapshot(root: &Path, scanned: &Path, containers: &[PathBuf]) -> BTreeMap<PathBuf, i64> {
+    std::iter::once(scanned)
+        .chain(containers.iter().map(PathBuf::as_path))
+        .filter(|dir| dir.strip_prefix(root).is_ok_and(|rest| rest.components().count() <= WATCH_DEPTH))
+        .filter_map(|dir| dir_mtime(dir).map(|mtime| (dir.to_path_buf(), mtime)))
+        .collect()
+}
+
+fn changed_dirs(root: &ScanRoot) -> Vec<PathBuf> {
+    if root.mtimes.is_empty() {
+        return vec![root.path.clone()];
+    }
+    let changed: Vec<&PathBuf> = root
+        .mtimes
+        .iter()
+        .filter(|(dir, mtime)| dir_mtime(dir) != Some(**mtime))
+        .map(|(dir, _)| dir)
+        .collect();
+    changed
+        .iter()
+        .filter(|dir| !changed.iter().any(|other| other != *dir && dir.starts_with(other)))
+        .filter(|dir| dir.is_dir())
+        .map(|dir| (*dir).clone())
+        .collect()
+}
+
+pub fn is_due(root: &ScanRoot, now: DateTime<Utc>, interval: Duration) -> bool {
+    match root.last_scan {
+        Some(last) => now - last >= interval,
+        None => true,
+    }
+}
+
+pub fn rescan(
+    db: &mut Database,
+    options: &ScanOptions,
+    now: DateTime<Utc>,
+    interval: Duration,
+    force: bool,
+) -> Result<RescanReport> {
+    let mut report = RescanReport::default();
+
+    for i in 0..db.roots.len() {
+        if !force && !is_due(&db.roots[i], now, interval) {
+            continue;
+        }
+        db.roots[i].last_scan = Some(now);
+        report.checked.push(db.roots[i].path.clone());
+
+        let root_path = db.roots[i].path.clone();
+        if !root_path.is_dir() {
+            continue;
+        }
+        let dirs = if force {
+            vec![root_path.clone()]
+        } else {
+            changed_dirs(&db.roots[i])
+        };
+
+        for dir in dirs {
+            let result = scanner::scan_subtree(&root_path, dir.clone(), options)?;
+            let mtimes = &mut db.roots[i].mtimes;
+            mtimes.retain(|watched, _| !watched.starts_with(&dir));
+            mtimes.extend(snapshot(&root_path, &dir, &result.containers));
+            report.scanned.push(dir.clone());
+
+            let added: Vec<PathBuf> = result
+                .repos
+                .iter()
+                .filter(|repo| !db.repositories.contains(repo) && !db.untracked.contains(repo))
+                .cloned()
+                .collect();
+            report.added.extend(added.iter().cloned());
+            db.add_repos(added);
+
+            for repo in &db.repositories {
+                if repo.starts_with(&dir)
+                    && !result.repos.contains(repo)
+                    && Repository::open(repo).is_err()
+                    && !report.missing.contains(repo)
+                {
+                    report.missing.push(repo.clone());
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+pub fn merge(latest: &mut Database, scanned: &Database, report: &RescanReport) {
+    let added: Vec<PathBuf> = report
+        .added
+        .iter()
+        .filter(|repo| !latest.untracked.contains(repo))
+        .cloned()
+        .collect();
+    latest.add_repos(added);
+    for root in latest.roots.iter_mut() {
+        if !report.checked.contains(&root.path) {
+            continue;
+        }
+        if let Some(scanned_root) = scanned.roots.iter().find(|r| r.path == root.path) {
+            root.last_scan = scanned_root.last_scan;
+            root.mtimes = scanned_root.mtimes.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn settle() {
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_rescan_announces_and_flags() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = fs::canonicalize(temp_dir.path()).unwrap();
+        Repository::init(root.join("work/api")).unwrap();
+
+        let mut db = Database::default();
+        db.add_root(&root).unwrap();
+        let options = ScanOptions::default();
+        let interval = Duration::minutes(60);
+        let now = Utc::now();
+
+        let report = rescan(&mut db, &options, now, interval, false).unwrap();
+        assert_eq!(report.added, vec![root.join("work/api")]);
+        assert!(db.roots[0].mtimes.contains_key(&root.join("work")));
+
+        Repository::init(root.join("work/web")).unwrap();
+        let report = rescan(&mut db, &options, now + Duration::minutes(10), interval, false).unwrap();
+        assert!(report.checked.is_empty());
+
+        fs::remove_dir_all(root.join("work/api")).unwrap();
+        let report = rescan(&mut db, &options, now + Duration::minutes(61), interval, false).unwrap();
+        assert_eq!(report.added, vec![root.join("work/web")]);
+        assert_eq!(report.missing, vec![root.join("work/api")]);
+        assert_eq!(report.scanned, vec![root.join("work")]);
+
+        let report = rescan(&mut db, &options, now + Duration::minutes(122), interval, false).unwrap();
+        assert_eq!(report.checked, vec![root.clone()]);
+        assert!(report.scanned.is_empty());
+
+        Repository::init(root.join("vendor/lib")).unwrap();
+        fs::remove_dir_all(root.join("work/web/.git")).unwrap();
+        db.add_repos(vec![root.join("vendor/lib")]);
+        let options = ScanOptions {
+            ignore: vec!["vendor".to_string()],
+            ..Default::default()
+        };
+        let report = rescan(&mut db, &options, now, interval, true).unwrap();
+        assert_eq!(report.missing, vec![root.join("work/api"), root.join("work/web")]);
+    }
+
+    #[test]
+    fn test_rescan_watches_shallow_dirs_only() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = fs::canonicalize(temp_dir.path()).unwrap();
+        Repository::init(root.join("work/team/service/api")).unwrap();
+        fs::create_dir_all(root.join("oss")).unwrap();
+
+        let mut db = Database::default();
+        db.add_root(&root).unwrap();
+        let options = ScanOptions::default();
+        let interval = Duration::minutes(60);
+        let now = Utc::now();
+
+        rescan(&mut db, &options, now, interval, false).unwrap();
+        let watched: Vec<&PathBuf> = db.roots[0].mtimes.keys().collect();
+        assert_eq!(watched, vec![&root, &root.join("oss"), &root.join("work"), &root.join("work/team")]);
+
+        settle();
+        Repository::init(root.join("oss/lib")).unwrap();
+        let report = rescan(&mut db, &options, now + Duration::minutes(61), interval, false).unwrap();
+        assert_eq!(report.scanned, vec![root.join("oss")]);
+        assert_eq!(report.added, vec![root.join("oss/lib")]);
+        assert_eq!(db.repositories.len(), 2);
+    }
+
+    #[test]
+    fn test_root_that_is_a_repo_is_not_rescanned_every_time() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = fs::canonicalize(temp_dir.path()).unwrap();
+        Repository::init(&root).unwrap();
+
+        let mut db = Database::default();
+        db.add_root(&root).unwrap();
+        let interval = Duration::minutes(60);
+        let now = Utc::now();
+
+        let report = rescan(&mut db, &ScanOptions::default(), now, interval, false).unwrap();
+        assert_eq!(report.added, vec![root.clone()]);
+        assert!(db.roots[0].mtimes.contains_key(&root));
+
+        let report = rescan(&mut db, &ScanOptions::default(), now + Duration::minutes(61), interval, false).unwrap();
+        assert_eq!(report.checked, vec![root.clone()]);
+        assert!(report.scanned.is_empty());
+    }
+
+    #[test]
+    fn test_untracked_repos_stay_untracked() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = fs::canonicalize(temp_dir.path()).unwrap();
+        Repository::init(root.join("work/api")).unwrap();
+        Repository::init(root.join("work/web")).unwrap();
+
+        let mut db = Database::default();
+        db.add_root(&root).unwrap();
+        let options = ScanOptions::default();
+        let interval = Duration::minutes(60);
+        let now = Utc::now();
+        rescan(&mut db, &options, now, interval, false).unwrap();
+
+        db.untrack_repos(&[root.join("work/api")]);
+        assert_eq!(db.untracked, vec![root.join("work/api")]);
+        let report = rescan(&mut db, &options, now, interval, true).unwrap();
+        assert!(report.added.is_empty());
+        assert_eq!(db.repositories, vec![root.join("work/web")]);
+
+        settle();
+        Repository::init(root.join("work/cli")).unwrap();
+        let report = rescan(&mut db, &options, now + Duration::minutes(61), interval, false).unwrap();
+        assert_eq!(report.added, vec![root.join("work/cli")]);
+
+        let mut latest = Database::default();
+        latest.untracked.push(root.join("work/cli"));
+        merge(&mut latest, &db, &report);
+        assert!(latest.repositories.is_empty());
+    }
+}
@@ -0,0 +1,426 @@
+use crate::git_ops;
+use crate::settings::{self, AiConfig, AppConfig};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, Utc};
+use git2::Repository;
+use reqwest::StatusCode;
+use reqwest::blocking::Client;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use url::Url;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub status: Status,
+    pub label: String,
+    pub detail: String,
+    pub hint: Option<String>,
+}
+
+impl Check {
+    pub fn ok(label: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            status: Status::Ok,
+            label: label.into(),
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    pub fn warn(label: impl Into<String>, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            status: Status::Warn,
+            label: label.into(),
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    pub fn fail(label: impl Into<String>, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            status: Status::Fail,
+            label: label.into(),
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+pub fn check_config_file(path: &Path, file_config: Result<AppConfig>) -> Check {
+    let label = "Config file";
+    if !path.exists() {
+        return Check::ok(label, format!("{:?} does not exist yet, defaults are used", path));
+    }
+    match file_config {
+        Err(e) => Check::fail(
+            label,
+            format!("{:?} could not be read: {:#}", path, e),
+            "Fix the file by hand or delete it to start over from the defaults.",
+        ),
+        Ok(config) => {
+            let secrets = settings::stored_secrets(&config).unwrap_or_default();
+            if !secrets.is_empty() && settings::is_world_readable(path) {
+                return Check::warn(
+                    label,
+                    format!("{:?} holds {} in plain text and other users can read it", path, secrets.join(", ")),
+                    format!(
+                        "Run `chmod 600 {}`. The API key can also move to ai.api_key_cmd, ai.api_key_file or ai.api_key_env.",
+                        path.display()
+                    ),
+                );
+            }
+            match settings::validate(&config) {
+                Ok(()) => Check::ok(label, format!("{:?}", path)),
+                Err(e) => Check::fail(
+                    label,
+                    format!("{:?}: {:#}", path, e),
+                    "Fix it with `yestergit config set <key> <value>`.",
+                ),
+            }
+        }
+    }
+}
+
+fn dir_is_writable(dir: &Path) -> bool {
+    tempfile::NamedTempFile::new_in(dir).is_ok()
+}
+
+pub fn check_database(path: &Path, load: impl FnOnce() -> Result<usize>) -> Vec<Check> {
+    let label = "Database";
+    let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    if dir.exists() && !dir_is_writable(&dir) {
+        return vec![Check::fail(
+            label,
+            format!("{:?} is not writable", dir),
+            "Fix the directory permissions or point YESTERGIT_DB_PATH somewhere writable.",
+        )];
+    }
+    if !path.exists() {
+        return vec![
+            Check::ok(label, format!("{:?} does not exist yet", path)),
+            Check::fail(
+                "Tracked repos",
+                "No repositories are tracked",
+                "Run `yestergit scan --path <dir>` to find your repositories.",
+            ),
+        ];
+    }
+
+    match load() {
+        Err(e) => vec![Check::fail(
+            label,
+            format!("{:?} could not be loaded: {:#}", path, e),
+            "Restore it from a backup or `yestergit import` an export.",
+        )],
+        Ok(0) => vec![
+            Check::ok(label, format!("{:?}", path)),
+            Check::fail(
+                "Tracked repos",
+                "No repositories are tracked",
+                "Run `yestergit scan --path <dir>` to find your repositories.",
+            ),
+        ],
+        Ok(count) => vec![
+            Check::ok(label, format!("{:?}", path)),
+            Check::ok("Tracked repos", format!("{} repositories", count)),
+        ],
+    }
+}
+
+pub fn check_window(since: DateTime<Utc>) -> Check {
+    let local = since.with_timezone(&Local);
+    Check::ok(
+        "Time window",
+        format!(
+            "since {} local time (UTC{}), {} UTC",
+            local.format("%a %d/%m %H:%M"),
+            local.format("%:z"),
+            since.format("%d/%m %H:%M")
+        ),
+    )
+}
+
+fn identity_of(repo: &Repository) -> Option<String> {
+    let config = repo.config().ok()?;
+    let name = config.get_string("user.name").ok();
+    let email = config.get_string("user.email").ok();
+    match (name, email) {
+        (Some(name), Some(email)) => Some(format!("{} <{}>", name, email)),
+        (Some(one), None) | (None, Some(one)) => Some(one),
+        (None, None) => None,
+    }
+}
+
+pub fn check_repo(path: &PathBuf, since: DateTime<Utc>, authors: &[String]) -> Check {
+    let label = path.to_string_lossy().to_string();
+    if !path.exists() {
+        return Check::fail(label, "path does not exist", "Run `yestergit prune` to stop tracking it.");
+    }
+    let repo = match Repository::open(path) {
+        Ok(repo) => repo,
+        Err(e) => {
+            return Check::fail(
+                label,
+                format!("cannot open: {}", e.message()),
+                "Check the permissions or run `git status` there.",
+            );
+        }
+    };
+
+    let head = match repo.head() {
+        Ok(head) => head.shorthand().unwrap_or("detached").to_string(),
+        Err(_) => return Check::warn(label, "no commits yet", "Nothing to report until the first commit."),
+    };
+
+    let identity = if authors.is_empty() {
+        match identity_of(&repo) {
+            Some(identity) => identity,
+            None => {
+                return Check::fail(
+                    label,
+                    format!("on {}, no git user.name or user.email", head),
+                    "Set them with `git config --global`, or list yourself under identity.authors.",
+                );
+            }
+        }
+    } else {
+        authors.join(", ")
+    };
+
+    let total = git_ops::fetch_all_commits(path, since).map(|c| c.len());
+    let mine = git_ops::fetch_commits(path, since, authors).map(|c| c.len());
+    match (total, mine) {
+        (Err(e), _) | (_, Err(e)) => Check::fail(
+            label,
+            format!("on {}, reading history failed: {:#}", head, e),
+            "Run `git log` there to see what git reports.",
+        ),
+        (Ok(total), Ok(0)) if total > 0 => Check::warn(
+            label,
+            format!("on {}, {} commits in the window but none by {}", head, total, identity),
+            "Check --author or identity.authors.",
+        ),
+        (Ok(total), Ok(mine)) => Check::ok(
+            label,
+            format!("on {}, {} of {} commits in the window by {}", head, mine, total, identity),
+        ),
+    }
+}
+
+pub fn models_url(api_url: &str) -> Result<Url> {
+    let mut url = Url::parse(api_url).with_context(|| format!("{:?} is not a valid URL.", api_url))?;
+    let path = url.path().trim_end_matches('/').to_string();
+    let base = if let Some(base) = path.strip_suffix("/chat/completions") {
+        base.to_string()
+    } else if let Some(at) = path.find("/v1") {
+        path[..at + 3].to_string()
+    } else {
+        format!("{}/v1", path)
+    };
+    url.set_path(&format!("{}/models", base));
+    Ok(url)
+}
+
+fn list_models(url: &Url, api_key: &str) -> Result<Result<Vec<String>, StatusCode>> {
+    let client = Client::builder().timeout(Duration::from_secs(5)).build()?;
+    let mut request = client.get(url.as_str());
+    if !api_key.is_empty() {
+        request = request.header("Authorization", format!("Bearer {}", api_key));
+    }
+    let res = request.send().context("Request failed.")?;
+
+    let status = res.status();
+    if !status.is_success() {
+        return Ok(Err(status));
+    }
+    let body: serde_json::Value = res.json().context("The response is not JSON.")?;
+    Ok(Ok(body["data"]
+        .as_array()
+        .map(|models| {
+            models
+                .iter()
+                .filter_map(|m| m["id"].as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()))
+}
+
+pub fn check_ai(config: &AiConfig) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    let api_key = match config.resolve_api_key() {
+        Ok(key) if key.is_empty() => {
+            checks.push(Check::ok("API key", "none set, fine for local models"));
+            key
+        }
+        Ok(key) => {
+            checks.push(Check::ok("API key", settings::mask(&key)));
+            key
+        }
+        Err(e) => {
+            checks.push(Check::fail("API key", format!("{:#}", e), "Fix the key source in the config."));
+            return checks;
+        }
+    };
+
+    let url = match models_url(&config.api_url) {
+        Ok(url) => url,
+        Err(e) => {
+            checks.push(Check::fail(
+                "AI endpoint",
+                format!("{:#}", e),
+                "Set it with `yestergit config set ai.api_url <url>`.",
+            ));
+            return checks;
+        }
+    };
+
+    match list_models(&url, &api_key) {
+        Ok(Err(status)) if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN => {
+            checks.push(Check::fail(
+                "AI endpoint",
+                format!("{} rejected the API key ({})", url, status),
+                "Check the API key.",
+            ));
+        }
+        Ok(Err(status)) => {
+            checks.push(Check::ok("AI endpoint", format!("{} is reachable", url.host_str().unwrap_or_default())));
+            checks.push(Check::warn(
+                "AI model",
+                format!("{} does not list models ({}), cannot confirm {:?}", url, status, config.model),
+                "Summaries may still work. Run `yestergit summarize` to find out.",
+            ));
+        }
+        Err(e) => {
+            checks.push(Check::fail(
+                "AI endpoint",
+                format!("cannot reach {}: {}", url, e.root_cause()),
+                "Check ai.api_url and that the server is running.",
+            ));
+        }
+        Ok(Ok(models)) if models.contains(&config.model) => {
+            checks.push(Check::ok("AI endpoint", format!("{} is reachable", url.host_str().unwrap_or_default())));
+            checks.push(Check::ok("AI model", format!("{:?} is available", config.model)));
+        }
+        Ok(Ok(models)) => {
+            checks.push(Check::ok("AI endpoint", format!("{} is reachable", url.host_str().unwrap_or_default())));
+            let mut listed = models.iter().take(10).cloned().collect::<Vec<_>>().join(", ");
+            if models.len() > 10 {
+                listed.push_str(", ...");
+            }
+            checks.push(Check::fail(
+                "AI model",
+                format!("{:?} is not offered. Available: {}", config.model, listed),
+                "Pick one with `yestergit config set ai.model <name>`.",
+            ));
+        }
+    }
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{Signature, Time};
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use tempfile::TempDir;
+
+    fn commit(repo: &Repository, author: &str) {
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let time = Time::new(Utc::now().timestamp() - 60, 0);
+        let signature = Signature::new(author, "dev@example.com", &time).unwrap();
+        let parents = match repo.head() {
+            Ok(head) => vec![repo.find_commit(head.target().unwrap()).unwrap()],
+            Err(_) => vec![],
+        };
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &signature, &signature, "work", &tree, &parents)
+            .unwrap();
+    }
+
+    fn serve_once(status: &'static str, body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+        });
+        format!("http://{}/v1/chat/completions", addr)
+    }
+
+    #[test]
+    fn test_models_url() {
+        let url = |s| models_url(s).unwrap().to_string();
+        assert_eq!(url("http://localhost:11434/v1/chat/completions"), "http://localhost:11434/v1/models");
+        assert_eq!(url("https://api.example.com/openai/v1/chat/completions/"), "https://api.example.com/openai/v1/models");
+        assert_eq!(url("https://api.example.com/v1/responses"), "https://api.example.com/v1/models");
+        assert_eq!(url("https://api.example.com"), "https://api.example.com/v1/models");
+        assert!(models_url("localhost").is_err());
+    }
+
+    #[test]
+    fn test_check_repo() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("api");
+        let repo = Repository::init(&path).unwrap();
+        let since = Utc::now() - chrono::Duration::days(1);
+
+        assert_eq!(check_repo(&path, since, &["Alice".to_string()]).status, Status::Warn);
+        commit(&repo, "Alice");
+        commit(&repo, "Bob");
+
+        let check = check_repo(&path, since, &["Alice".to_string()]);
+        assert_eq!(check.status, Status::Ok);
+        assert!(check.detail.contains("1 of 2 commits"));
+
+        let check = check_repo(&path, since, &["Carol".to_string()]);
+        assert_eq!(check.status, Status::Warn);
+        assert!(check.detail.contains("none by Carol"));
+
+        let gone = temp_dir.path().join("gone");
+        assert_eq!(check_repo(&gone, since, &[]).status, Status::Fail);
+    }
+
+    #[test]
+    fn test_check_ai() {
+        let mut ai = AppConfig::default().ai;
+        ai.model = "llama3".to_string();
+
+        ai.api_url = serve_once("200 OK", r#"{"data":[{"id":"llama3"},{"id":"qwen"}]}"#);
+        let checks = check_ai(&ai);
+        assert!(checks.iter().all(|c| c.status == Status::Ok));
+
+        ai.api_url = serve_once("200 OK", r#"{"data":[{"id":"qwen"}]}"#);
+        let checks = check_ai(&ai);
+        assert_eq!(checks.last().unwrap().status, Status::Fail);
+        assert!(checks.last().unwrap().detail.contains("qwen"));
+
+        ai.api_url = serve_once("401 Unauthorized", "{}");
+        assert_eq!(check_ai(&ai).last().unwrap().status, Status::Fail);
+
+        ai.api_url = serve_once("404 Not Found", "{}");
+        assert_eq!(check_ai(&ai).last().unwrap().status, Status::Warn);
+    }
+}
@@ -0,0 +1,289 @@
+use crate::html;
+use crate::settings::{EmailConfig, SmtpSecurity};
+use anyhow::{bail, Context, Result};
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use std::time::Duration;
+
+pub struct ReportLine {
+    pub time: String,
+    pub kind: &'static str,
+    pub source: String,
+    pub message: String,
+}
+
+pub struct EmailReport {
+    pub subject: String,
+    pub heading: String,
+    pub summary: Option<String>,
+    pub lines: Vec<ReportLine>,
+    pub warnings: Vec<String>,
+}
+
+fn underline(title: &str) -> String {
+    format!("{}\n{}\n", title, "-".repeat(title.chars().count()))
+}
+
+pub fn render_text(report: &EmailReport) -> String {
+    let mut out = format!("{}\n\n", report.heading);
+
+    if let Some(summary) = &report.summary {
+        out.push_str(&underline("Summary"));
+        out.push_str(summary.trim());
+        out.push_str("\n\n");
+    }
+
+    out.push_str(&underline("Activity"));
+    if report.lines.is_empty() {
+        out.push_str("No events for this time.\n");
+    }
+    let width = report.lines.iter().map(|l| l.source.chars().count()).max().unwrap_or(0);
+    for line in &report.lines {
+        out.push_str(&format!(
+            "{}  {:<4}  {:<width$}  {}\n",
+            line.time,
+            line.kind,
+            line.source,
+            line.message.lines().next().unwrap_or_default(),
+            width = width
+        ));
+    }
+
+    if !report.warnings.is_empty() {
+        out.push('\n');
+        out.push_str(&underline("Warnings"));
+        for warning in &report.warnings {
+            out.push_str(&format!("- {}\n", warning));
+        }
+    }
+    out
+}
+
+pub fn render_html(report: &EmailReport) -> String {
+    let mut out = String::from(
+        "<!DOCTYPE html>\n<html>\n<body style=\"font-family: sans-serif; color: #222;\">\n",
+    );
+    out.push_str(&format!("<h2>{}</h2>\n", html::escape(&report.heading)));
+
+    if let Some(summary) = &report.summary {
+        out.push_str("<h3>Summary</h3>\n");
+        out.push_str(&html::paragraphs(summary));
+    }
+
+    out.push_str("<h3>Activity</h3>\n");
+    if report.lines.is_empty() {
+        out.push_str("<p>No events for this time.</p>\n");
+    } else {
+        out.push_str("<table style=\"border-collapse: collapse;\" cellpadding=\"4\">\n");
+        out.push_str("<tr style=\"text-align: left; border-bottom: 1px solid #ccc;\"><th>Time</th><th>Type</th><th>Source / Repo</th><th>Message</th></tr>\n");
+        for line in &report.lines {
+            out.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                html::escape(&line.time),
+                line.kind,
+                html::escape(&line.source),
+                html::escape(line.message.lines().next().unwrap_or_default())
+            ));
+        }
+        out.push_str("</table>\n");
+    }
+
+    if !report.warnings.is_empty() {
+        out.push_str("<h3>Warnings</h3>\n<ul>\n");
+        for warning in &report.warnings {
+            out.push_str(&format!("<li>{}</li>\n", html::escape(warning)));
+        }
+        out.push_str("</ul>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn mailbox(address: &str, field: &str) -> Result<Mailbox> {
+    address
+        .parse()
+        .with_context(|| format!("{:?} is not a valid {} address.", address, field))
+}
+
+pub fn build_message(
+    config: &EmailConfig,
+    to: &[String],
+    cc: &[String],
+    report: &EmailReport,
+) -> Result<Message> {
+    if config.from.is_empty() {
+        bail!("No sender is configured. Set it with `yestergit config set email.from <address>`.");
+    }
+    if to.is_empty() {
+        bail!("No recipients. Pass --to or set email.to.");
+    }
+
+    let mut builder = Message::builder()
+        .from(mailbox(&config.from, "sender")?)
+        .subject(&report.subject);
+    for address in to {
+        builder = builder.to(mailbox(address, "recipient")?);
+    }
+    for address in cc {
+        builder = builder.cc(mailbox(address, "cc")?);
+    }
+
+    builder
+        .multipart(MultiPart::alternative_plain_html(
+            render_text(report),
+            render_html(report),
+        ))
+        .context("Could not build the email.")
+}
+
+pub fn send(config: &EmailConfig, message: &Message) -> Result<()> {
+    if config.host.is_empty() {
+        bail!("No SMTP server is configured. Set it with `yestergit config set email.host <host>`.");
+    }
+
+    let mut builder = match config.security {
+        SmtpSecurity::Starttls => SmtpTransport::starttls_relay(&config.host)?,
+        SmtpSecurity::Tls => SmtpTransport::relay(&config.host)?,
+        SmtpSecurity::None => SmtpTransport::builder_dangerous(&config.host),
+    }
+    .port(config.port)
+    .timeout(Some(Duration::from_secs(30)));
+
+    if !config.username.is_empty() {
+        builder = builder.credentials(Credentials::new(
+            config.username.clone(),
+            config.password.clone(),
+        ));
+    }
+
+    builder
+        .build()
+        .send(message)
+        .with_context(|| format!("Sending mail through {}:{} failed.", config.host, config.port))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn report() -> EmailReport {
+        EmailReport {
+            subject: "Status report".to_string(),
+            heading: "Status report since 12/10".to_string(),
+            summary: Some("Shipped the <login> fix.\n\nNext: billing.".to_string()),
+            lines: vec![ReportLine {
+                time: "13/10 09:30".to_string(),
+                kind: "Git",
+                source: "api".to_string(),
+                message: "fix: login & logout".to_string(),
+            }],
+            warnings: vec!["web: Could not find git repo".to_string()],
+        }
+    }
+
+    fn config(port: u16) -> EmailConfig {
+        EmailConfig {
+            host: "127.0.0.1".to_string(),
+            port,
+            security: SmtpSecurity::None,
+            from: "Dev <dev@example.com>".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn smtp_stand_in() -> (u16, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut received = Vec::new();
+            writer.write_all(b"220 localhost ESMTP\r\n").unwrap();
+
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 0 {
+                let command = line.trim_end().to_string();
+                line.clear();
+                let upper = command.to_uppercase();
+                if upper.starts_with("EHLO") || upper.starts_with("HELO") {
+                    writer.write_all(b"250-localhost\r\n250 8BITMIME\r\n").unwrap();
+                } else if upper == "DATA" {
+                    writer.write_all(b"354 go ahead\r\n").unwrap();
+                    let mut data = String::new();
+                    loop {
+                        reader.read_line(&mut line).unwrap();
+                        if line == ".\r\n" {
+                            break;
+                        }
+                        data.push_str(&line);
+                        line.clear();
+                    }
+                    line.clear();
+                    received.push(data);
+                    writer.write_all(b"250 queued\r\n").unwrap();
+                } else if upper == "QUIT" {
+                    writer.write_all(b"221 bye\r\n").unwrap();
+                    break;
+                } else {
+                    received.push(command);
+                    writer.write_all(b"250 ok\r\n").unwrap();
+                }
+            }
+            received
+        });
+        (port, handle)
+    }
+
+    #[test]
+    fn test_render() {
+        let text = render_text(&report());
+        assert!(text.contains("Summary\n-------\nShipped the <login> fix."));
+        assert!(text.contains("13/10 09:30  Git   api  fix: login & logout"));
+        assert!(text.contains("- web: Could not find git repo"));
+
+        let html = render_html(&report());
+        assert!(html.contains("<p>Shipped the &lt;login&gt; fix.</p>"));
+        assert!(html.contains("<td>fix: login &amp; logout</td>"));
+    }
+
+    #[test]
+    fn test_build_message() {
+        let to = vec!["Boss <boss@example.com>".to_string()];
+        let cc = vec!["team@example.com".to_string()];
+        let message = build_message(&config(25), &to, &cc, &report()).unwrap();
+        let formatted = String::from_utf8(message.formatted()).unwrap();
+        assert!(formatted.contains("To: Boss <boss@example.com>"));
+        assert!(formatted.contains("Cc: team@example.com"));
+        assert!(formatted.contains("multipart/alternative"));
+        assert!(formatted.contains("text/plain"));
+        assert!(formatted.contains("text/html"));
+
+        assert!(build_message(&config(25), &[], &cc, &report()).is_err());
+        let bad = vec!["not an address".to_string()];
+        assert!(build_message(&config(25), &bad, &[], &report()).is_err());
+    }
+
+    #[test]
+    fn test_send_to_smtp_stand_in() {
+        let (port, handle) = smtp_stand_in();
+        let to = vec!["boss@example.com".to_string()];
+        let cc = vec!["team@example.com".to_string()];
+        let config = config(port);
+        let message = build_message(&config, &to, &cc, &report()).unwrap();
+        send(&config, &message).unwrap();
+
+        let received = handle.join().unwrap();
+        assert!(received.iter().any(|c| c == "MAIL FROM:<dev@example.com>"));
+        assert!(received.iter().any(|c| c == "RCPT TO:<boss@example.com>"));
+        assert!(received.iter().any(|c| c == "RCPT TO:<team@example.com>"));
+        let data = received.last().unwrap();
+        assert!(data.contains("Subject: Status report"));
+        assert!(data.contains("text/html"));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::conventional;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Local, TimeZone, Utc};
 use git2::{Repository, Sort};
@@ -7,29 +8,94 @@ use std::path::PathBuf;
 pub struct CommitLog {
     pub message: String,
     pub author: String,
+    pub email: String,
     pub date: DateTime<Local>,
     pub hash: String,
+    pub kind: Option<String>,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub tickets: Vec<String>,
+    pub stats: Option<DiffStat>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DiffStat {
+    pub insertions: usize,
+    pub deletions: usize,
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RepoHealth {
+    pub exists: bool,
+    pub readable: bool,
+    pub has_head: bool,
+    pub last_commit: Option<DateTime<Local>>,
+    pub error: Option<String>,
+}
+
+impl RepoHealth {
+    pub fn is_healthy(&self) -> bool {
+        self.exists && self.readable
+    }
+}
+
+pub fn repo_health(repo_path: &PathBuf) -> RepoHealth {
+    let mut health = RepoHealth {
+        exists: repo_path.exists(),
+        ..Default::default()
+    };
+    if !health.exists {
+        health.error = Some("Path does not exist".to_string());
+        return health;
+    }
+
+    let repo = match Repository::open(repo_path) {
+        Ok(repo) => repo,
+        Err(e) => {
+            health.error = Some(e.message().to_string());
+            return health;
+        }
+    };
+    health.readable = true;
+
+    if let Ok(commit) = repo.head().and_then(|head| head.peel_to_commit()) {
+        health.has_head = true;
+        health.last_commit = Utc
+            .timestamp_opt(commit.time().seconds(), 0)
+            .single()
+            .map(DateTime::from);
+    }
+    health
 }
 
 pub fn fetch_commits(
     repo_path: &PathBuf,
     since: DateTime<Utc>,
-    author_filter: Option<String>,
+    authors: &[String],
 ) -> Result<Vec<CommitLog>> {
-    let repo = Repository::open(repo_path)
-        .with_context(|| format!("Could not find git repo: {:?}", repo_path))?;
-
-    let mut revwalk = repo.revwalk()?;
+    fetch(repo_path, since, authors, false)
+}
 
-    if revwalk.push_head().is_err() {
-        return Ok(Vec::new());
-    }
+pub fn fetch_commits_with_stats(
+    repo_path: &PathBuf,
+    since: DateTime<Utc>,
+    authors: &[String],
+) -> Result<Vec<CommitLog>> {
+    fetch(repo_path, since, authors, true)
+}
 
-    revwalk.set_sorting(Sort::TIME)?;
+fn fetch(
+    repo_path: &PathBuf,
+    since: DateTime<Utc>,
+    authors: &[String],
+    with_stats: bool,
+) -> Result<Vec<CommitLog>> {
+    let repo = Repository::open(repo_path)
+        .with_context(|| format!("Could not find git repo: {:?}", repo_path))?;
 
-    let filters: Vec<String> = match author_filter {
-        Some(f) => vec![f.to_lowercase()],
-        None => {
+    let filters: Vec<String> = match authors {
+        [] => {
             let mut auto_filters = Vec::new();
             if let Ok(config) = repo.config() {
                 if let Ok(name) = config.get_string("user.name") {
@@ -41,7 +107,60 @@ pub fn fetch_commits(
             }
             auto_filters
         }
+        authors => authors.iter().map(|a| a.to_lowercase()).collect(),
     };
+    walk_commits(&repo, since, &filters, with_stats)
+}
+
+pub fn fetch_all_commits(repo_path: &PathBuf, since: DateTime<Utc>) -> Result<Vec<CommitLog>> {
+    let repo = Repository::open(repo_path)
+        .with_context(|| format!("Could not find git repo: {:?}", repo_path))?;
+
+    walk_commits(&repo, since, &[], false)
+}
+
+fn diff_stat(repo: &Repository, commit: &git2::Commit) -> Result<DiffStat> {
+    if commit.parent_count() > 1 {
+        return Ok(DiffStat::default());
+    }
+
+    let tree = commit.tree()?;
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+    let stats = diff.stats()?;
+    let files = diff
+        .deltas()
+        .filter_map(|d| d.new_file().path().or_else(|| d.old_file().path()))
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+
+    Ok(DiffStat {
+        insertions: stats.insertions(),
+        deletions: stats.deletions(),
+        files,
+    })
+}
+
+fn walk_commits(
+    repo: &Repository,
+    since: DateTime<Utc>,
+    filters: &[String],
+    with_stats: bool,
+) -> Result<Vec<CommitLog>> {
+    let mut revwalk = repo.revwalk()?;
+
+    if repo.is_bare() {
+        revwalk.push_glob("refs/heads")?;
+    } else if revwalk.push_head().is_err() {
+        return Ok(Vec::new());
+    }
+
+    revwalk.set_sorting(Sort::TIME)?;
+
     let mut logs = Vec::new();
 
     for oid in revwalk {
@@ -72,12 +191,24 @@ pub fn fetch_commits(
         }
         let full_message = commit.message().unwrap_or("");
         let short_message = full_message.lines().next().unwrap_or("").to_string();
+        let parsed = conventional::parse(full_message);
+        let stats = if with_stats {
+            Some(diff_stat(repo, &commit)?)
+        } else {
+            None
+        };
 
         logs.push(CommitLog {
             message: short_message,
             author: author_name,
+            email: author_email,
             date: DateTime::from(commit_datetime_utc),
             hash: oid.to_string()[0..7].to_string(),
+            kind: parsed.kind,
+            scope: parsed.scope,
+            breaking: parsed.breaking,
+            tickets: parsed.tickets,
+            stats,
         });
     }
     Ok(logs)
@@ -138,13 +269,85 @@ mod tests {
         create_commit(&repo, "Second commit", -50, "Bob");
 
         let since = Utc::now() - chrono::Duration::hours(1);
-        let logs = fetch_commits(&temp_dir.path().to_path_buf(), since, None).unwrap();
+        let logs = fetch_commits(&temp_dir.path().to_path_buf(), since, &[]).unwrap();
 
         assert_eq!(logs.len(), 2);
         assert_eq!(logs[0].message, "Second commit");
         assert_eq!(logs[1].message, "Initial commit");
     }
 
+    #[test]
+    fn test_fetch_commits_conventional() {
+        let (temp_dir, repo) = setup_repo();
+
+        create_commit(&repo, "Initial commit", -100, "Alice");
+        create_commit(&repo, "feat(api)!: Second commit\n\nRefs: PROJ-42", -50, "Bob");
+
+        let since = Utc::now() - chrono::Duration::hours(1);
+        let logs = fetch_commits(&temp_dir.path().to_path_buf(), since, &[]).unwrap();
+
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].message, "feat(api)!: Second commit");
+        assert_eq!(logs[0].kind.as_deref(), Some("feat"));
+        assert_eq!(logs[0].scope.as_deref(), Some("api"));
+        assert!(logs[0].breaking);
+        assert_eq!(logs[0].tickets, vec!["PROJ-42"]);
+        assert_eq!(logs[1].kind, None);
+        assert!(logs[1].tickets.is_empty());
+    }
+
+    #[test]
+    fn test_fetch_commits_with_stats() {
+        let (temp_dir, repo) = setup_repo();
+
+        let file_path = temp_dir.path().join("test.txt");
+        let mut file = File::create(&file_path).unwrap();
+        writeln!(file, "one\ntwo").unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("test.txt")).unwrap();
+        index.write().unwrap();
+
+        create_commit(&repo, "Add file", -100, "Alice");
+
+        let since = Utc::now() - chrono::Duration::hours(1);
+        let logs = fetch_commits_with_stats(
+            &temp_dir.path().to_path_buf(),
+            since,
+            &["Alice".to_string()],
+        )
+        .unwrap();
+
+        let stats = logs[0].stats.as_ref().unwrap();
+        assert_eq!(stats.insertions, 2);
+        assert_eq!(stats.deletions, 0);
+        assert_eq!(stats.files, vec!["test.txt"]);
+    }
+
+    #[test]
+    fn test_repo_health() {
+        let (temp_dir, repo) = setup_repo();
+        let path = temp_dir.path().to_path_buf();
+
+        let health = repo_health(&path);
+        assert!(health.is_healthy());
+        assert!(!health.has_head);
+
+        create_commit(&repo, "Initial commit", -100, "Alice");
+        let health = repo_health(&path);
+        assert!(health.has_head);
+        assert!(health.last_commit.is_some());
+
+        let missing = repo_health(&path.join("missing"));
+        assert!(!missing.exists);
+        assert!(!missing.is_healthy());
+
+        let plain_dir = TempDir::new().unwrap();
+        let not_repo = repo_health(&plain_dir.path().to_path_buf());
+        assert!(not_repo.exists);
+        assert!(!not_repo.readable);
+    }
+
     #[test]
     fn test_fetch_commits_time_filter() {
         let (temp_dir, repo) = setup_repo();
@@ -159,7 +362,7 @@ mod tests {
         create_commit(&repo, "New commit", -60, "Alice");
 
         let since = Utc::now() - chrono::Duration::hours(1);
-        let logs = fetch_commits(&temp_dir.path().to_path_buf(), since, None).unwrap();
+        let logs = fetch_commits(&temp_dir.path().to_path_buf(), since, &[]).unwrap();
 
         assert_eq!(logs.len(), 1);
         assert_eq!(logs[0].message, "New commit");
@@ -183,7 +386,7 @@ mod tests {
         let alice_logs = fetch_commits(
             &temp_dir.path().to_path_buf(),
             since,
-            Some("Alice".to_string()),
+            &["Alice".to_string()],
         )
         .unwrap();
         assert_eq!(alice_logs.len(), 1);
@@ -192,10 +395,14 @@ mod tests {
         let bob_logs = fetch_commits(
             &temp_dir.path().to_path_buf(),
             since,
-            Some("Bob".to_string()),
+            &["Bob".to_string()],
         )
         .unwrap();
         assert_eq!(bob_logs.len(), 1);
         assert_eq!(bob_logs[0].author, "Bob");
+
+        let all_logs = fetch_all_commits(&temp_dir.path().to_path_buf(), since).unwrap();
+        assert_eq!(all_logs.len(), 2);
+        assert_eq!(all_logs[0].email, "email@example.com");
     }
 }
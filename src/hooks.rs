@@ -0,0 +1,228 @@
+use anyhow::{bail, Context, Result};
+use git2::Repository;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const HOOKS: &[&str] = &["post-commit", "post-checkout"];
+
+const MARKER: &str = "# Installed by yestergit.";
+const CHAINED_SUFFIX: &str = ".yestergit-chained";
+
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+pub fn script(hook: &str, exe: &Path, profile: Option<&str>) -> String {
+    let mut command = quote(&exe.to_string_lossy());
+    if let Some(profile) = profile {
+        command.push_str(&format!(" --profile {}", quote(profile)));
+    }
+    format!(
+        "#!/bin/sh\n\
+         {} Remove with `yestergit hooks uninstall`.\n\
+         status=0\n\
+         if [ -x \"$0{}\" ]; then\n    \"$0{}\" \"$@\" || status=$?\n\
+         fi\n\
+         head=$(git rev-parse -q --verify HEAD)\n\
+         branch=$(git symbolic-ref -q --short HEAD)\n\
+         {} hooks record {} --head \"$head\" --branch \"$branch\" -- \"$@\" </dev/null >/dev/null 2>&1 &\n\
+         exit $status\n",
+        MARKER, CHAINED_SUFFIX, CHAINED_SUFFIX, command, hook
+    )
+}
+
+pub fn hooks_dir(repo_path: &Path) -> Result<PathBuf> {
+    let repo = Repository::open(repo_path)
+        .with_context(|| format!("Could not open git repo at {:?}", repo_path))?;
+    let configured = repo
+        .config()
+        .and_then(|c| c.get_path("core.hooksPath"))
+        .ok();
+    Ok(match configured {
+        Some(dir) if dir.is_absolute() => dir,
+        Some(dir) => repo.workdir().unwrap_or_else(|| repo.path()).join(dir),
+        None => repo.commondir().join("hooks"),
+    })
+}
+
+fn is_ours(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|content| content.contains(MARKER))
+}
+
+fn chained_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(CHAINED_SUFFIX);
+    PathBuf::from(name)
+}
+
+fn make_executable(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+pub fn install(repo_path: &Path, exe: &Path, profile: Option<&str>) -> Result<Vec<String>> {
+    let dir = hooks_dir(repo_path)?;
+    fs::create_dir_all(&dir).with_context(|| format!("Could not create {:?}", dir))?;
+
+    let mut chained = Vec::new();
+    for hook in HOOKS {
+        let path = dir.join(hook);
+        if path.symlink_metadata().is_ok() && !is_ours(&path) {
+            let backup = chained_path(&path);
+            if backup.symlink_metadata().is_ok() {
+                bail!("{:?} already exists, move it out of the way first.", backup);
+            }
+            fs::rename(&path, &backup)
+                .with_context(|| format!("Could not move {:?} out of the way", path))?;
+            chained.push(hook.to_string());
+        }
+        fs::write(&path, script(hook, exe, profile))
+            .with_context(|| format!("Could not write {:?}", path))?;
+        make_executable(&path)?;
+    }
+    Ok(chained)
+}
+
+pub fn uninstall(repo_path: &Path) -> Result<bool> {
+    let dir = hooks_dir(repo_path)?;
+    let mut removed = false;
+    for hook in HOOKS {
+        let path = dir.join(hook);
+        if !is_ours(&path) {
+            continue;
+        }
+        fs::remove_file(&path).with_context(|| format!("Could not remove {:?}", path))?;
+        let chained = chained_path(&path);
+        if chained.symlink_metadata().is_ok() {
+            fs::rename(&chained, &path)
+                .with_context(|| format!("Could not restore {:?}", chained))?;
+        }
+        removed = true;
+    }
+    Ok(removed)
+}
+
+pub fn repo_root(repo: &Repository) -> PathBuf {
+    let common = repo.commondir();
+    match common.file_name() {
+        Some(name) if name == ".git" => common.parent().unwrap_or(common).to_path_buf(),
+        _ => common.to_path_buf(),
+    }
+}
+
+fn short_id(oid: git2::Oid) -> String {
+    oid.to_string()[0..7].to_string()
+}
+
+pub fn activity(
+    repo: &Repository,
+    event: &str,
+    head: &str,
+    branch: &str,
+    args: &[String],
+) -> Result<Option<String>> {
+    let head = git2::Oid::from_str(head).with_context(|| format!("{:?} is not a commit id.", head))?;
+    let branch = match branch {
+        "" => format!("detached HEAD at {}", short_id(head)),
+        name => name.to_string(),
+    };
+    match event {
+        "post-checkout" => {
+            if args.get(2).map(String::as_str) != Some("1") {
+                return Ok(None);
+            }
+            Ok(Some(format!("Switched to {}", branch)))
+        }
+        "post-commit" => {
+            let commit = repo.find_commit(head)?;
+            Ok(Some(format!(
+                "Committed {} on {}: {}",
+                short_id(commit.id()),
+                branch,
+                commit.summary().unwrap_or_default()
+            )))
+        }
+        _ => bail!("Unknown hook {:?}, expected one of {}.", event, HOOKS.join(", ")),
+    }
+}
+
+pub fn committed_hash(message: &str) -> Option<&str> {
+    message.strip_prefix("Committed ")?.split_whitespace().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+    use tempfile::TempDir;
+
+    fn commit(repo: &Repository, message: &str) {
+        let sig = Signature::now("Dev", "dev@example.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents).unwrap();
+    }
+
+    #[test]
+    fn test_install_chains_and_uninstall_restores() {
+        let temp_dir = TempDir::new().unwrap();
+        Repository::init(temp_dir.path()).unwrap();
+        let dir = hooks_dir(temp_dir.path()).unwrap();
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("post-commit"), "#!/bin/sh\necho mine\n").unwrap();
+
+        let exe = Path::new("/opt/it's here/yestergit");
+        let chained = install(temp_dir.path(), exe, Some("work")).unwrap();
+        assert_eq!(chained, vec!["post-commit"]);
+        assert!(HOOKS.iter().all(|hook| is_ours(&dir.join(hook))));
+        let script = fs::read_to_string(dir.join("post-commit")).unwrap();
+        assert!(script.contains("'/opt/it'\\''s here/yestergit' --profile 'work' hooks record post-commit --head"));
+        assert!(dir.join("post-commit.yestergit-chained").exists());
+
+        assert!(install(temp_dir.path(), exe, None).unwrap().is_empty());
+        assert_eq!(
+            fs::read_to_string(dir.join("post-commit.yestergit-chained")).unwrap(),
+            "#!/bin/sh\necho mine\n"
+        );
+
+        assert!(uninstall(temp_dir.path()).unwrap());
+        assert_eq!(fs::read_to_string(dir.join("post-commit")).unwrap(), "#!/bin/sh\necho mine\n");
+        assert!(!dir.join("post-checkout").exists());
+        assert!(!dir.join("post-commit.yestergit-chained").exists());
+        assert!(!uninstall(temp_dir.path()).unwrap());
+    }
+
+    #[test]
+    fn test_activity_messages() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        commit(&repo, "fix: login\n\nlong body");
+        let head = repo.head().unwrap().target().unwrap().to_string();
+        let hash = &head[0..7];
+
+        let args = |flag: &str| vec!["a".to_string(), "b".to_string(), flag.to_string()];
+        assert_eq!(
+            activity(&repo, "post-checkout", &head, "PROJ-12-billing", &args("1")).unwrap().unwrap(),
+            "Switched to PROJ-12-billing"
+        );
+        assert_eq!(
+            activity(&repo, "post-checkout", &head, "", &args("1")).unwrap().unwrap(),
+            format!("Switched to detached HEAD at {}", hash)
+        );
+        assert!(activity(&repo, "post-checkout", &head, "main", &args("0")).unwrap().is_none());
+
+        let message = activity(&repo, "post-commit", &head, "PROJ-12-billing", &[]).unwrap().unwrap();
+        assert_eq!(message, format!("Committed {} on PROJ-12-billing: fix: login", hash));
+        assert_eq!(committed_hash(&message), Some(hash));
+        assert!(committed_hash("Switched to main").is_none());
+        assert!(activity(&repo, "pre-push", &head, "main", &[]).is_err());
+        assert_eq!(repo_root(&repo).canonicalize().unwrap(), temp_dir.path().canonicalize().unwrap());
+    }
+}
@@ -0,0 +1,93 @@
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use std::collections::BTreeMap;
+
+pub struct WorkEvent {
+    pub date: DateTime<Local>,
+    pub repo: String,
+    pub ticket: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeEntry {
+    pub day: NaiveDate,
+    pub repo: String,
+    pub ticket: Option<String>,
+    pub minutes: i64,
+}
+
+pub fn estimate(mut events: Vec<WorkEvent>, max_gap: Duration, first_commit: Duration) -> Vec<TimeEntry> {
+    events.sort_by_key(|e| e.date);
+
+    let mut totals: BTreeMap<(NaiveDate, String, Option<String>), i64> = BTreeMap::new();
+    let mut previous: Option<DateTime<Local>> = None;
+
+    for event in events {
+        let credit = match previous {
+            Some(prev) if event.date - prev <= max_gap => event.date - prev,
+            _ => first_commit,
+        };
+        previous = Some(event.date);
+
+        *totals
+            .entry((event.date.date_naive(), event.repo, event.ticket))
+            .or_default() += credit.num_minutes();
+    }
+
+    totals
+        .into_iter()
+        .map(|((day, repo, ticket), minutes)| TimeEntry {
+            day,
+            repo,
+            ticket,
+            minutes,
+        })
+        .collect()
+}
+
+pub fn total_by<K: Ord>(entries: &[TimeEntry], key: impl Fn(&TimeEntry) -> K) -> BTreeMap<K, i64> {
+    let mut totals = BTreeMap::new();
+    for entry in entries {
+        *totals.entry(key(entry)).or_default() += entry.minutes;
+    }
+    totals
+}
+
+pub fn format_hours(minutes: i64) -> String {
+    format!("{:.2}", minutes as f64 / 60.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn event(hour: u32, minute: u32, repo: &str, ticket: Option<&str>) -> WorkEvent {
+        WorkEvent {
+            date: Local.with_ymd_and_hms(2026, 3, 2, hour, minute, 0).unwrap(),
+            repo: repo.to_string(),
+            ticket: ticket.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_estimate_sessions() {
+        let events = vec![
+            event(10, 30, "api", Some("PROJ-1")),
+            event(9, 0, "api", Some("PROJ-1")),
+            event(11, 0, "web", None),
+            event(16, 0, "api", None),
+        ];
+
+        let entries = estimate(events, Duration::minutes(120), Duration::minutes(30));
+
+        let by_repo = total_by(&entries, |e| e.repo.clone());
+        assert_eq!(by_repo["api"], 30 + 90 + 30);
+        assert_eq!(by_repo["web"], 30);
+
+        let by_ticket = total_by(&entries, |e| e.ticket.clone());
+        assert_eq!(by_ticket[&Some("PROJ-1".to_string())], 120);
+        assert_eq!(by_ticket[&None], 60);
+
+        assert_eq!(format_hours(150), "2.50");
+    }
+}
@@ -0,0 +1,301 @@
+use crate::db::{ManuelEntry, SummaryRecord};
+use crate::git_ops::CommitLog;
+use chrono::{DateTime, Local, NaiveDate};
+use std::collections::{BTreeMap, BTreeSet};
+
+const STYLE: &str = "
+body { font-family: -apple-system, 'Segoe UI', Roboto, sans-serif; color: #1f2328; max-width: 960px; margin: 2rem auto; padding: 0 1rem; line-height: 1.5; }
+h1 { margin-bottom: 0; }
+.muted { color: #656d76; }
+.cards { display: flex; gap: 1rem; flex-wrap: wrap; margin: 1.5rem 0; }
+.card { border: 1px solid #d0d7de; border-radius: 6px; padding: 0.75rem 1rem; min-width: 8rem; }
+.card b { display: block; font-size: 1.5rem; }
+.summary { background: #f6f8fa; border-left: 4px solid #0969da; padding: 0.5rem 1rem; }
+.add { color: #1a7f37; }
+.del { color: #cf222e; }
+ul.events { list-style: none; padding-left: 0; }
+ul.events li { margin: 0.2rem 0; }
+time { font-family: ui-monospace, monospace; color: #656d76; margin-right: 0.5rem; }
+.note { font-style: italic; }
+details { border: 1px solid #d0d7de; border-radius: 6px; margin: 0.5rem 0; padding: 0.5rem 1rem; }
+summary { cursor: pointer; font-weight: 600; }
+table { border-collapse: collapse; width: 100%; margin-top: 0.5rem; font-size: 0.9rem; }
+th, td { text-align: left; padding: 0.25rem 0.5rem; border-bottom: 1px solid #eaeef2; vertical-align: top; }
+code { font-family: ui-monospace, monospace; }
+.warnings { color: #9a6700; }
+";
+
+pub struct Page<'a> {
+    pub title: String,
+    pub since: DateTime<Local>,
+    pub commits: &'a [(String, CommitLog)],
+    pub notes: &'a [ManuelEntry],
+    pub summary: Option<&'a SummaryRecord>,
+    pub warnings: Vec<String>,
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn paragraphs(text: &str) -> String {
+    text.trim()
+        .split("\n\n")
+        .map(|p| format!("<p>{}</p>\n", escape(p.trim()).replace('\n', "<br>\n")))
+        .collect()
+}
+
+fn first_line(message: &str) -> &str {
+    message.lines().next().unwrap_or_default()
+}
+
+fn diff_label(insertions: usize, deletions: usize) -> String {
+    format!(
+        "<span class=\"add\">+{}</span> <span class=\"del\">&minus;{}</span>",
+        insertions, deletions
+    )
+}
+
+fn totals<'a>(commits: impl Iterator<Item = &'a CommitLog>) -> (usize, usize, usize) {
+    let mut files = BTreeSet::new();
+    let (mut insertions, mut deletions) = (0, 0);
+    for stats in commits.filter_map(|c| c.stats.as_ref()) {
+        insertions += stats.insertions;
+        deletions += stats.deletions;
+        files.extend(stats.files.iter());
+    }
+    (insertions, deletions, files.len())
+}
+
+enum Item<'a> {
+    Commit(&'a CommitLog),
+    Note(&'a ManuelEntry),
+}
+
+type RepoItems<'a> = BTreeMap<String, Vec<(DateTime<Local>, Item<'a>)>>;
+
+fn timeline(page: &Page) -> String {
+    let mut days: BTreeMap<NaiveDate, RepoItems> = BTreeMap::new();
+    for (repo, commit) in page.commits {
+        days.entry(commit.date.date_naive())
+            .or_default()
+            .entry(repo.clone())
+            .or_default()
+            .push((commit.date, Item::Commit(commit)));
+    }
+    for note in page.notes {
+        let date = DateTime::<Local>::from(note.date);
+        days.entry(date.date_naive())
+            .or_default()
+            .entry(note.repo.clone().unwrap_or_else(|| "Notes".to_string()))
+            .or_default()
+            .push((date, Item::Note(note)));
+    }
+
+    let mut out = String::new();
+    for (day, repos) in days {
+        out.push_str(&format!("<h3>{}</h3>\n", day.format("%A %d/%m/%Y")));
+        for (repo, mut items) in repos {
+            items.sort_by_key(|(date, _)| *date);
+            out.push_str(&format!("<h4>{}</h4>\n<ul class=\"events\">\n", escape(&repo)));
+            for (date, item) in items {
+                let body = match item {
+                    Item::Commit(c) => match &c.stats {
+                        Some(stats) => format!(
+                            "{} {}",
+                            escape(first_line(&c.message)),
+                            diff_label(stats.insertions, stats.deletions)
+                        ),
+                        None => escape(first_line(&c.message)),
+                    },
+                    Item::Note(n) => format!(
+                        "<span class=\"note\">{}: {}</span>",
+                        n.kind.label(),
+                        escape(&n.message)
+                    ),
+                };
+                out.push_str(&format!("<li><time>{}</time>{}</li>\n", date.format("%H:%M"), body));
+            }
+            out.push_str("</ul>\n");
+        }
+    }
+    out
+}
+
+fn repo_details(page: &Page) -> String {
+    let mut by_repo: BTreeMap<&str, Vec<&CommitLog>> = BTreeMap::new();
+    for (repo, commit) in page.commits {
+        by_repo.entry(repo).or_default().push(commit);
+    }
+
+    let mut out = String::new();
+    for (repo, mut commits) in by_repo {
+        commits.sort_by_key(|c| c.date);
+        let (insertions, deletions, files) = totals(commits.iter().copied());
+        out.push_str(&format!(
+            "<details>\n<summary>{} &middot; {} commits &middot; {} files &middot; {}</summary>\n",
+            escape(repo),
+            commits.len(),
+            files,
+            diff_label(insertions, deletions)
+        ));
+        out.push_str("<table>\n<tr><th>Time</th><th>Commit</th><th>Message</th><th>Author</th><th>Changes</th></tr>\n");
+        for c in commits {
+            let (changes, files) = match &c.stats {
+                Some(stats) => (
+                    diff_label(stats.insertions, stats.deletions),
+                    stats.files.iter().map(|f| escape(f)).collect::<Vec<_>>().join("<br>"),
+                ),
+                None => (String::new(), String::new()),
+            };
+            out.push_str(&format!(
+                "<tr><td>{}</td><td><code>{}</code></td><td>{}<div class=\"muted\">{}</div></td><td>{}</td><td>{}</td></tr>\n",
+                c.date.format("%d/%m %H:%M"),
+                escape(&c.hash),
+                escape(first_line(&c.message)),
+                files,
+                escape(&c.author),
+                changes
+            ));
+        }
+        out.push_str("</table>\n</details>\n");
+    }
+    out
+}
+
+pub fn render(page: &Page) -> String {
+    let (insertions, deletions, _) = totals(page.commits.iter().map(|(_, c)| c));
+    let repos: BTreeSet<&str> = page.commits.iter().map(|(r, _)| r.as_str()).collect();
+    let files: BTreeSet<(&str, &str)> = page
+        .commits
+        .iter()
+        .filter_map(|(repo, c)| c.stats.as_ref().map(|s| (repo, s)))
+        .flat_map(|(repo, s)| s.files.iter().map(move |f| (repo.as_str(), f.as_str())))
+        .collect();
+    let files = files.len();
+
+    let mut out = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    out.push_str(&format!("<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n", escape(&page.title), STYLE));
+    out.push_str(&format!("<h1>{}</h1>\n", escape(&page.title)));
+    out.push_str(&format!(
+        "<p class=\"muted\">Since {} &middot; generated {}</p>\n",
+        page.since.format("%d/%m/%Y %H:%M"),
+        Local::now().format("%d/%m/%Y %H:%M")
+    ));
+
+    out.push_str("<div class=\"cards\">\n");
+    for (value, label) in [
+        (page.commits.len().to_string(), "commits"),
+        (repos.len().to_string(), "repositories"),
+        (files.to_string(), "files changed"),
+        (format!("<span class=\"add\">+{}</span>", insertions), "lines added"),
+        (format!("<span class=\"del\">&minus;{}</span>", deletions), "lines removed"),
+        (page.notes.len().to_string(), "notes"),
+    ] {
+        out.push_str(&format!("<div class=\"card\"><b>{}</b>{}</div>\n", value, label));
+    }
+    out.push_str("</div>\n");
+
+    if let Some(summary) = page.summary {
+        out.push_str("<h2>Summary</h2>\n<div class=\"summary\">\n");
+        out.push_str(&paragraphs(&summary.summary));
+        out.push_str(&format!(
+            "<p class=\"muted\">{} &middot; {}</p>\n</div>\n",
+            escape(&summary.model),
+            DateTime::<Local>::from(summary.date).format("%d/%m/%Y %H:%M")
+        ));
+    }
+
+    out.push_str("<h2>Timeline</h2>\n");
+    if page.commits.is_empty() && page.notes.is_empty() {
+        out.push_str("<p>No events for this time.</p>\n");
+    } else {
+        out.push_str(&timeline(page));
+        out.push_str("<h2>Repositories</h2>\n");
+        out.push_str(&repo_details(page));
+    }
+
+    if !page.warnings.is_empty() {
+        out.push_str("<h2>Warnings</h2>\n<ul class=\"warnings\">\n");
+        for warning in &page.warnings {
+            out.push_str(&format!("<li>{}</li>\n", escape(warning)));
+        }
+        out.push_str("</ul>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::NoteKind;
+    use crate::git_ops::DiffStat;
+    use chrono::{TimeZone, Utc};
+
+    fn commit(day: u32, hour: u32, message: &str, insertions: usize) -> CommitLog {
+        CommitLog {
+            message: message.to_string(),
+            author: "Alice".to_string(),
+            email: "alice@example.com".to_string(),
+            date: Local.with_ymd_and_hms(2026, 10, day, hour, 0, 0).unwrap(),
+            hash: format!("abc{}{}", day, hour),
+            kind: None,
+            scope: None,
+            breaking: false,
+            tickets: Vec::new(),
+            stats: Some(DiffStat {
+                insertions,
+                deletions: 1,
+                files: vec!["src/lib.rs".to_string()],
+            }),
+        }
+    }
+
+    #[test]
+    fn test_render_page() {
+        let commits = vec![
+            ("api".to_string(), commit(12, 9, "fix <login>", 10)),
+            ("web".to_string(), commit(12, 11, "style", 2)),
+            ("api".to_string(), commit(13, 10, "add tests", 30)),
+        ];
+        let notes = vec![ManuelEntry {
+            id: 1,
+            message: "Sprint planning".to_string(),
+            date: Utc.with_ymd_and_hms(2026, 10, 13, 12, 0, 0).unwrap(),
+            tags: Vec::new(),
+            repo: None,
+            kind: NoteKind::Manual,
+        }];
+        let summary = SummaryRecord {
+            date: Utc.with_ymd_and_hms(2026, 10, 13, 18, 0, 0).unwrap(),
+            model: "llama3".to_string(),
+            summary: "Fixed login.\n\nWrote tests.".to_string(),
+        };
+        let page = Page {
+            title: "Sprint review".to_string(),
+            since: Local.with_ymd_and_hms(2026, 10, 12, 0, 0, 0).unwrap(),
+            commits: &commits,
+            notes: &notes,
+            summary: Some(&summary),
+            warnings: vec!["old: Could not find git repo".to_string()],
+        };
+        let html = render(&page);
+
+        assert!(!html.contains("http://") && !html.contains("https://") && !html.contains("<script"));
+        assert!(html.contains("<b>3</b>commits") && html.contains("<b>2</b>repositories"));
+        assert!(html.contains("<b>2</b>files changed"));
+        assert!(html.contains("+42</span>"));
+        assert!(html.contains("Monday 12/10/2026") && html.contains("Tuesday 13/10/2026"));
+        assert!(html.contains("fix &lt;login&gt;") && !html.contains("fix <login>"));
+        assert!(html.contains("<p>Fixed login.</p>"));
+        assert!(html.contains("Note: Sprint planning"));
+        assert_eq!(html.matches("<details>").count(), 2);
+        assert!(html.contains("api &middot; 2 commits &middot; 1 files"));
+        assert!(html.contains("old: Could not find git repo"));
+    }
+}
@@ -1,7 +1,24 @@
 pub mod ai;
+pub mod archive;
+pub mod cache;
 pub mod commands;
 pub mod config;
+pub mod conventional;
+pub mod dates;
 pub mod db;
+pub mod discovery;
+pub mod doctor;
+pub mod email;
 pub mod git_ops;
+pub mod hours;
+pub mod hooks;
+pub mod html;
+pub mod migrations;
+pub mod post;
+pub mod profile;
+pub mod refine;
 pub mod scanner;
 pub mod settings;
+pub mod stats;
+pub mod storage;
+pub mod team;
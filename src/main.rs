@@ -1,27 +1,134 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::Parser;
 use yestergit::{
     commands,
-    config::{Args, Commands},
+    profile,
+    config::{Args, CacheAction, Commands, ConfigAction, DbAction, HooksAction, NoteAction, OutputFormat, RootsAction, TeamAction},
 };
 
 fn main() -> Result<()> {
     let args = Args::parse();
+    profile::init(args.profile.clone())?;
+
+    if (args.format == OutputFormat::Html || args.open)
+        && !matches!(args.command, None | Some(Commands::Check { .. }))
+    {
+        bail!("--format html and --open only work with the report and `check`.");
+    }
 
     match &args.command {
-        Some(Commands::Scan { path }) => {
-            commands::scan(path.clone())?;
+        Some(Commands::Scan {
+            path,
+            max_depth,
+            follow_links,
+            ignore,
+            submodules,
+        }) => {
+            commands::scan(path.clone(), *max_depth, *follow_links, ignore.clone(), *submodules)?;
         }
         Some(Commands::Check { path }) => {
             commands::check(path.clone(), &args)?;
         }
-        Some(Commands::List) => {
-            commands::list()?;
+        Some(Commands::List { status }) => {
+            commands::list(*status, &args)?;
         }
-        Some(Commands::Note { message }) => {
-            commands::note(message.clone())?;
+        Some(Commands::Untrack { target, all }) => {
+            commands::untrack(target.clone(), *all)?;
+        }
+        Some(Commands::Repo {
+            target,
+            set_alias,
+            clear_alias,
+            set_group,
+            clear_group,
+            enable,
+            disable,
+        }) => {
+            let alias = if *clear_alias {
+                Some(None)
+            } else {
+                set_alias.clone().map(Some)
+            };
+            let group = if *clear_group {
+                Some(None)
+            } else {
+                set_group.clone().map(Some)
+            };
+            let enabled = if *enable {
+                Some(true)
+            } else if *disable {
+                Some(false)
+            } else {
+                None
+            };
+            commands::repo(target.clone(), alias, group, enabled)?;
         }
+        Some(Commands::Prune { dry_run }) => {
+            commands::prune(*dry_run)?;
+        }
+        Some(Commands::Roots { action }) => match action {
+            RootsAction::Add { path } => {
+                commands::roots_add(path.clone())?;
+            }
+            RootsAction::Rm { path } => {
+                commands::roots_rm(path.clone())?;
+            }
+            RootsAction::List => {
+                commands::roots_list()?;
+            }
+            RootsAction::Scan => {
+                commands::roots_scan()?;
+            }
+        },
+        Some(Commands::Note { action, message }) => match action {
+            Some(NoteAction::Add {
+                message,
+                at,
+                tags,
+                repo,
+            }) => {
+                commands::note(message.clone(), at.clone(), tags.clone(), repo.clone())?;
+            }
+            Some(NoteAction::List { tag, repo, activity }) => {
+                commands::note_list(tag.clone(), repo.clone(), *activity, &args)?;
+            }
+            Some(NoteAction::Edit {
+                id,
+                message,
+                at,
+                tags,
+                clear_tags,
+                repo,
+                clear_repo,
+            }) => {
+                commands::note_edit(
+                    *id,
+                    message.clone(),
+                    at.clone(),
+                    tags.clone(),
+                    *clear_tags,
+                    repo.clone(),
+                    *clear_repo,
+                )?;
+            }
+            Some(NoteAction::Rm { id }) => {
+                commands::note_rm(*id)?;
+            }
+            None => match message {
+                Some(message) => commands::note(message.clone(), None, Vec::new(), None)?,
+                None => commands::note_list(None, None, false, &args)?,
+            },
+        },
+        Some(Commands::Config {
+            action: Some(action),
+            ..
+        }) => match action {
+            ConfigAction::Get { key } => commands::config_get(key.clone())?,
+            ConfigAction::Set { key, value } => commands::config_set(key, value)?,
+            ConfigAction::Unset { key } => commands::config_unset(key)?,
+        },
         Some(Commands::Config {
+            action: None,
             set_key,
             set_url,
             set_model,
@@ -36,9 +143,92 @@ fn main() -> Result<()> {
                 set_lang.clone(),
             )?;
         }
-        Some(Commands::Summarize) => {
-            commands::summarize(&args)?;
+        Some(Commands::Summarize {
+            no_cache,
+            regenerate,
+            interactive,
+            save,
+            post,
+            yes,
+        }) => {
+            commands::summarize(*no_cache, *regenerate, *interactive, *save, post, *yes, &args)?;
+        }
+        Some(Commands::Email {
+            to,
+            cc,
+            dry_run,
+            no_cache,
+        }) => {
+            commands::email(to, cc, *dry_run, *no_cache, &args)?;
+        }
+        Some(Commands::Cache { action }) => match action {
+            CacheAction::Clear => {
+                commands::cache_clear()?;
+            }
+        },
+        Some(Commands::Db { action }) => match action {
+            DbAction::Migrate { to } => {
+                commands::db_migrate(*to)?;
+            }
+        },
+        Some(Commands::Export {
+            file,
+            include_secrets,
+        }) => {
+            commands::export(file.clone(), *include_secrets)?;
+        }
+        Some(Commands::Import {
+            file,
+            with_settings,
+        }) => {
+            commands::import(file.clone(), *with_settings)?;
+        }
+        Some(Commands::Hours {
+            max_gap,
+            first_commit,
+        }) => {
+            commands::hours(*max_gap, *first_commit, &args)?;
+        }
+        Some(Commands::Stats { weeks, top }) => {
+            commands::stats(*weeks, *top, &args)?;
+        }
+        Some(Commands::Doctor) => {
+            commands::doctor(&args)?;
         }
+        Some(Commands::Hooks { action }) => match action {
+            HooksAction::Install { repo, all } => {
+                commands::hooks_install(repo.clone(), *all)?;
+            }
+            HooksAction::Uninstall { repo, all } => {
+                commands::hooks_uninstall(repo.clone(), *all)?;
+            }
+            HooksAction::Record {
+                event,
+                head,
+                branch,
+                args,
+            } => {
+                commands::hooks_record(event, head, branch, args)?;
+            }
+        },
+        Some(Commands::Team {
+            action,
+            summarize,
+            no_cache,
+        }) => match action {
+            Some(TeamAction::Add { name, aliases }) => {
+                commands::team_add(name.clone(), aliases.clone())?;
+            }
+            Some(TeamAction::Remove { name }) => {
+                commands::team_remove(name.clone())?;
+            }
+            Some(TeamAction::Members) => {
+                commands::team_members()?;
+            }
+            None => {
+                commands::team(*summarize, *no_cache, &args)?;
+            }
+        },
         None => {
             commands::report_all(&args)?;
         }
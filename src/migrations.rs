@@ -0,0 +1,199 @@
+use anyhow::{bail, Context, Result};
+use rusqlite::Connection;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+pub const CURRENT_VERSION: u32 = 2;
+
+const UNVERSIONED: u32 = 1;
+
+type Step = fn(&mut Value) -> Result<()>;
+
+const STEPS: &[Step] = &[v1_to_v2];
+
+const SQLITE_STEPS: &[&str] = &[
+    "CREATE TABLE IF NOT EXISTS state (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS notes (
+        id INTEGER PRIMARY KEY,
+        message TEXT NOT NULL,
+        date TEXT NOT NULL,
+        tags TEXT NOT NULL DEFAULT '[]',
+        repo TEXT
+    );
+    CREATE INDEX IF NOT EXISTS notes_date ON notes (date);
+    CREATE TABLE IF NOT EXISTS history (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        date TEXT NOT NULL,
+        model TEXT NOT NULL,
+        summary TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS history_date ON history (date);",
+    "ALTER TABLE notes ADD COLUMN kind TEXT NOT NULL DEFAULT 'manual';",
+];
+
+pub fn migrate_sqlite(conn: &mut Connection) -> Result<()> {
+    let from = conn.pragma_query_value(None, "user_version", |row| row.get::<_, i64>(0))? as usize;
+    if from > SQLITE_STEPS.len() {
+        bail!(
+            "The database uses SQLite schema version {}, but this yestergit only knows up to {}. Please upgrade yestergit.",
+            from,
+            SQLITE_STEPS.len()
+        );
+    }
+
+    for (i, step) in SQLITE_STEPS.iter().enumerate().skip(from) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(step)
+            .with_context(|| format!("Migrating the SQLite database from version {} failed.", i))?;
+        tx.pragma_update(None, "user_version", i as i64 + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+pub fn version_of(db: &Value) -> Result<u32> {
+    match db.get("schema_version") {
+        None => Ok(UNVERSIONED),
+        Some(version) => version
+            .as_u64()
+            .map(|v| v as u32)
+            .context("`schema_version` must be a number."),
+    }
+}
+
+pub fn migrate(db: &mut Value) -> Result<u32> {
+    let from = version_of(db)?;
+    if from > CURRENT_VERSION {
+        bail!(
+            "The database uses schema version {}, but this yestergit only knows up to {}. Please upgrade yestergit.",
+            from,
+            CURRENT_VERSION
+        );
+    }
+
+    for (i, step) in STEPS.iter().enumerate().skip(from.saturating_sub(1) as usize) {
+        step(db).with_context(|| format!("Migrating the database from version {} failed.", i + 1))?;
+        db["schema_version"] = json!(i as u32 + 2);
+    }
+    Ok(from)
+}
+
+pub fn backup_path(db_path: &Path, version: u32) -> PathBuf {
+    let mut name = db_path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".v{}.bak", version));
+    db_path.with_file_name(name)
+}
+
+fn v1_to_v2(db: &mut Value) -> Result<()> {
+    let db = db.as_object_mut().context("The database is not a JSON object.")?;
+    let stored_next = db.get("next_note_id").and_then(Value::as_u64).unwrap_or(0);
+
+    let entries = db
+        .entry("entries")
+        .or_insert_with(|| json!([]))
+        .as_array_mut()
+        .context("`entries` is not a list.")?;
+
+    let id_of = |entry: &Value| entry.get("id").and_then(Value::as_u64).unwrap_or(0);
+    let after_max = entries.iter().map(id_of).max().unwrap_or(0) + 1;
+    let mut next = stored_next.max(after_max);
+
+    for entry in entries.iter_mut() {
+        if id_of(entry) == 0 {
+            entry["id"] = json!(next);
+            next += 1;
+        }
+    }
+
+    db.insert("next_note_id".to_string(), json!(next));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn load_fixture(name: &str) -> (TempDir, PathBuf, Database) {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("db.json");
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name);
+        fs::copy(fixture, &path).unwrap();
+        let db = Database::load_from(&path).unwrap();
+        (temp_dir, path, db)
+    }
+
+    #[test]
+    fn test_load_v1_baseline() {
+        let (_dir, path, db) = load_fixture("db_v1_baseline.json");
+
+        assert_eq!(db.schema_version, CURRENT_VERSION);
+        assert_eq!(db.repositories.len(), 2);
+        let ids: Vec<u64> = db.entries.iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+        assert_eq!(db.next_note_id, 3);
+        assert!(!backup_path(&path, 1).exists());
+
+        Database::update_at(&path, |db| {
+            assert_eq!(db.next_note_id, 3);
+            Ok(())
+        })
+        .unwrap();
+        let backup = backup_path(&path, 1);
+        assert!(backup.exists());
+        assert!(!fs::read_to_string(backup).unwrap().contains("schema_version"));
+
+        let saved: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(version_of(&saved).unwrap(), CURRENT_VERSION);
+    }
+
+    #[test]
+    fn test_load_v1_with_partial_ids() {
+        let (_dir, _path, db) = load_fixture("db_v1_notes.json");
+
+        let ids: Vec<u64> = db.entries.iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![4, 7, 8]);
+        assert_eq!(db.next_note_id, 9);
+        assert_eq!(db.entries[0].tags, vec!["pairing"]);
+        assert_eq!(db.history.len(), 1);
+        assert_eq!(db.repo_meta.len(), 1);
+    }
+
+    #[test]
+    fn test_load_current_version_untouched() {
+        let (_dir, path, db) = load_fixture("db_v2.json");
+
+        assert_eq!(db.entries.len(), 1);
+        assert_eq!(db.next_note_id, 2);
+        assert!(!backup_path(&path, 2).exists());
+    }
+
+    #[test]
+    fn test_sqlite_migrations() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(SQLITE_STEPS[0]).unwrap();
+        conn.execute("INSERT INTO notes (id, message, date) VALUES (1, 'old', '2026-03-01')", [])
+            .unwrap();
+
+        migrate_sqlite(&mut conn).unwrap();
+        migrate_sqlite(&mut conn).unwrap();
+        let kind: String = conn
+            .query_row("SELECT kind FROM notes WHERE id = 1", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(kind, "manual");
+
+        conn.pragma_update(None, "user_version", SQLITE_STEPS.len() as i64 + 1).unwrap();
+        assert!(migrate_sqlite(&mut conn).is_err());
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let mut db = json!({ "schema_version": CURRENT_VERSION + 1, "repositories": [] });
+        assert!(migrate(&mut db).is_err());
+    }
+}
@@ -0,0 +1,376 @@
+use crate::settings::PostConfig;
+use anyhow::{bail, Context, Result};
+use reqwest::blocking::Client;
+use serde_json::{json, Value};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sink {
+    Slack,
+    Teams,
+    Mattermost,
+    Webhook(Option<String>),
+}
+
+impl FromStr for Sink {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.split_once('=') {
+            Some(("webhook", url)) if !url.is_empty() => Ok(Sink::Webhook(Some(url.to_string()))),
+            None => match input.to_lowercase().as_str() {
+                "slack" => Ok(Sink::Slack),
+                "teams" => Ok(Sink::Teams),
+                "mattermost" => Ok(Sink::Mattermost),
+                "webhook" => Ok(Sink::Webhook(None)),
+                _ => Err(format!(
+                    "unknown sink {:?}, expected slack, teams, mattermost, webhook or webhook=<url>",
+                    input
+                )),
+            },
+            _ => Err(format!("expected webhook=<url>, got {:?}", input)),
+        }
+    }
+}
+
+impl fmt::Display for Sink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Sink::Slack => write!(f, "Slack"),
+            Sink::Teams => write!(f, "Teams"),
+            Sink::Mattermost => write!(f, "Mattermost"),
+            Sink::Webhook(_) => write!(f, "webhook"),
+        }
+    }
+}
+
+impl Sink {
+    pub fn url(&self, config: &PostConfig) -> Result<String> {
+        let (url, key) = match self {
+            Sink::Webhook(Some(url)) => return Ok(url.clone()),
+            Sink::Webhook(None) => (&config.webhook, "post.webhook"),
+            Sink::Slack => (&config.slack, "post.slack"),
+            Sink::Teams => (&config.teams, "post.teams"),
+            Sink::Mattermost => (&config.mattermost, "post.mattermost"),
+        };
+        if url.is_empty() {
+            bail!(
+                "No {} webhook is configured. Set it with `yestergit config set {} <url>`.",
+                self,
+                key
+            );
+        }
+        Ok(url.clone())
+    }
+
+    pub fn payload(&self, title: &str, summary: &str) -> Value {
+        match self {
+            Sink::Slack => json!({
+                "text": format!("*{}*\n{}", escape_slack(title), to_slack(summary)),
+            }),
+            Sink::Teams => json!({
+                "type": "message",
+                "attachments": [{
+                    "contentType": "application/vnd.microsoft.card.adaptive",
+                    "content": {
+                        "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+                        "type": "AdaptiveCard",
+                        "version": "1.4",
+                        "body": [
+                            { "type": "TextBlock", "text": title, "weight": "Bolder", "size": "Medium", "wrap": true },
+                            { "type": "TextBlock", "text": to_teams(summary), "wrap": true },
+                        ],
+                    },
+                }],
+            }),
+            Sink::Mattermost => json!({
+                "text": format!("#### {}\n{}", title, summary.trim()),
+            }),
+            Sink::Webhook(_) => json!({
+                "title": title,
+                "text": to_plain(summary),
+                "markdown": summary.trim(),
+            }),
+        }
+    }
+}
+
+pub fn send(url: &str, payload: &Value) -> Result<()> {
+    let client = Client::builder().timeout(Duration::from_secs(15)).build()?;
+    let res = client
+        .post(url)
+        .json(payload)
+        .send()
+        .context("Webhook request failed. Check the URL.")?;
+
+    let status = res.status();
+    if !status.is_success() {
+        let error_text = res.text().unwrap_or_default();
+        bail!("Webhook failed. (Status {}): {}", status, error_text);
+    }
+    Ok(())
+}
+
+fn heading(line: &str) -> Option<&str> {
+    let rest = line.trim_start_matches('#');
+    (rest.len() < line.len() && rest.starts_with(' ')).then(|| rest.trim())
+}
+
+fn bullet(line: &str) -> Option<&str> {
+    line.strip_prefix("- ").or_else(|| line.strip_prefix("* "))
+}
+
+fn convert_links(text: &str, link: impl Fn(&str, &str) -> String) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(open) = rest.find('[') {
+        let Some(close) = rest[open..].find("](").map(|i| open + i) else {
+            break;
+        };
+        let Some(end) = rest[close..].find(')').map(|i| close + i) else {
+            break;
+        };
+        out.push_str(&rest[..open]);
+        out.push_str(&link(&rest[open + 1..close], &rest[close + 2..end]));
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn map_lines(markdown: &str, line: impl Fn(&str) -> String) -> String {
+    markdown.trim().lines().map(|l| line(l.trim_end())).collect::<Vec<_>>().join("\n")
+}
+
+fn escape_slack(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+struct Inline {
+    code: fn(&str) -> String,
+    strong: fn(&str) -> String,
+    em: fn(&str) -> String,
+}
+
+fn opens(prev: Option<char>) -> bool {
+    prev.is_none_or(|c| c.is_whitespace() || "([{\"'".contains(c))
+}
+
+fn closes(next: Option<char>) -> bool {
+    next.is_none_or(|c| c.is_whitespace() || ".,;:!?)]}\"'".contains(c))
+}
+
+fn find_closer(chars: &[char], from: usize, marker: char, len: usize) -> Option<usize> {
+    (from + 1..chars.len()).take_while(|&j| chars[j] != '`').find(|&j| {
+        chars[j..].iter().take(len).filter(|&&c| c == marker).count() == len
+            && chars.get(j + len) != Some(&marker)
+            && chars[j - 1] != marker
+            && !chars[j - 1].is_whitespace()
+            && closes(chars.get(j + len).copied())
+    })
+}
+
+fn convert_inline(text: &str, style: &Inline) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '`'
+            && let Some(end) = chars[i + 1..].iter().position(|&d| d == '`').map(|p| i + 1 + p)
+        {
+            out.push_str(&(style.code)(&chars[i + 1..end].iter().collect::<String>()));
+            i = end + 1;
+            continue;
+        }
+        if c == '*' || c == '_' {
+            let len = if chars.get(i + 1) == Some(&c) { 2 } else { 1 };
+            let starts = opens(i.checked_sub(1).map(|p| chars[p]))
+                && chars.get(i + len).is_some_and(|n| !n.is_whitespace() && *n != c);
+            if starts && let Some(end) = find_closer(&chars, i + len, c, len) {
+                let inner = convert_inline(&chars[i + len..end].iter().collect::<String>(), style);
+                out.push_str(&if len == 2 { (style.strong)(&inner) } else { (style.em)(&inner) });
+                i = end + len;
+                continue;
+            }
+            out.extend(&chars[i..i + len]);
+            i += len;
+            continue;
+        }
+        out.push(c);
+        i += 1;
+    }
+    out
+}
+
+fn slack_inline(text: &str) -> String {
+    let text = convert_inline(
+        &escape_slack(text),
+        &Inline {
+            code: |code| format!("`{}`", code),
+            strong: |inner| format!("*{}*", inner),
+            em: |inner| format!("_{}_", inner),
+        },
+    );
+    convert_links(&text, |label, url| format!("<{}|{}>", url, label))
+}
+
+pub fn to_slack(markdown: &str) -> String {
+    map_lines(markdown, |line| {
+        if let Some(title) = heading(line) {
+            format!("*{}*", slack_inline(title).trim_matches('*'))
+        } else if let Some(item) = bullet(line) {
+            format!("• {}", slack_inline(item))
+        } else {
+            slack_inline(line)
+        }
+    })
+}
+
+pub fn to_teams(markdown: &str) -> String {
+    map_lines(markdown, |line| match heading(line) {
+        Some(title) => format!("**{}**", title.trim_matches('*')),
+        None => line.to_string(),
+    })
+}
+
+pub fn to_plain(markdown: &str) -> String {
+    map_lines(markdown, |line| {
+        let line = heading(line).unwrap_or(line);
+        let line = match bullet(line) {
+            Some(item) => format!("- {}", item),
+            None => line.to_string(),
+        };
+        let line = convert_inline(
+            &line,
+            &Inline {
+                code: str::to_string,
+                strong: str::to_string,
+                em: str::to_string,
+            },
+        );
+        convert_links(&line, |label, url| format!("{} ({})", label, url))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    const SUMMARY: &str = "## Yesterday\n- Fixed the **login** bug, see [PROJ-1](https://jira/PROJ-1)\n* Reviewed *two* PRs & <docs>";
+
+    fn serve(statuses: &'static [&'static str]) -> (String, thread::JoinHandle<Vec<(String, String)>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hooks/abc", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for status in statuses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut length = 0;
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    if let Some((name, value)) = line.split_once(':')
+                        && name.eq_ignore_ascii_case("content-length")
+                    {
+                        length = value.trim().parse().unwrap();
+                    }
+                    line.clear();
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                let path = request_line.split_whitespace().nth(1).unwrap_or_default().to_string();
+                requests.push((path, String::from_utf8(body).unwrap()));
+                write!(stream, "HTTP/1.1 {}\r\nContent-Length: 4\r\nConnection: close\r\n\r\nnope", status).unwrap();
+            }
+            requests
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_parse_sinks() {
+        assert_eq!("slack".parse(), Ok(Sink::Slack));
+        assert_eq!("Teams".parse(), Ok(Sink::Teams));
+        assert_eq!("webhook".parse(), Ok(Sink::Webhook(None)));
+        assert_eq!(
+            "webhook=https://example.com/hook?a=b".parse(),
+            Ok(Sink::Webhook(Some("https://example.com/hook?a=b".to_string())))
+        );
+        assert!("discord".parse::<Sink>().is_err());
+        assert!("slack=https://x".parse::<Sink>().is_err());
+
+        let config = PostConfig::default();
+        assert!(Sink::Slack.url(&config).is_err());
+    }
+
+    #[test]
+    fn test_markdown_conversion() {
+        assert_eq!(
+            to_slack(SUMMARY),
+            "*Yesterday*\n• Fixed the *login* bug, see <https://jira/PROJ-1|PROJ-1>\n• Reviewed _two_ PRs &amp; &lt;docs&gt;"
+        );
+        assert_eq!(
+            to_teams(SUMMARY),
+            "**Yesterday**\n- Fixed the **login** bug, see [PROJ-1](https://jira/PROJ-1)\n* Reviewed *two* PRs & <docs>"
+        );
+        assert_eq!(
+            to_plain(SUMMARY),
+            "Yesterday\n- Fixed the login bug, see PROJ-1 (https://jira/PROJ-1)\n- Reviewed two PRs & <docs>"
+        );
+
+        let literal = "Cleaned src/*.rs and src/*/*.rs, a * b, `x**y*_z_` and **bold** *em* 2 * 3 in my_var_name";
+        assert_eq!(
+            to_slack(literal),
+            "Cleaned src/*.rs and src/*/*.rs, a * b, `x**y*_z_` and *bold* _em_ 2 * 3 in my_var_name"
+        );
+        assert_eq!(
+            to_plain(literal),
+            "Cleaned src/*.rs and src/*/*.rs, a * b, x**y*_z_ and bold em 2 * 3 in my_var_name"
+        );
+        assert_eq!(to_slack("**bold** then *"), "*bold* then *");
+        assert_eq!(to_slack("**unclosed and *em*"), "**unclosed and _em_");
+        assert_eq!(to_plain("(*aside*) __strong__."), "(aside) strong.");
+    }
+
+    #[test]
+    fn test_send_to_mock_server() {
+        let (url, handle) = serve(&["200 OK", "500 Internal Server Error"]);
+
+        for sink in [Sink::Slack, Sink::Mattermost] {
+            let payload = sink.payload("Daily update", SUMMARY);
+            let result = send(&url, &payload);
+            if sink == Sink::Slack {
+                result.unwrap();
+            } else {
+                assert!(result.unwrap_err().to_string().contains("500"));
+            }
+        }
+
+        let bodies = handle.join().unwrap();
+        assert_eq!(bodies[0].0, "/hooks/abc");
+        let slack: Value = serde_json::from_str(&bodies[0].1).unwrap();
+        assert!(slack["text"].as_str().unwrap().starts_with("*Daily update*\n*Yesterday*"));
+        let mattermost: Value = serde_json::from_str(&bodies[1].1).unwrap();
+        assert!(mattermost["text"].as_str().unwrap().starts_with("#### Daily update\n## Yesterday"));
+    }
+
+    #[test]
+    fn test_teams_and_webhook_payloads() {
+        let teams = Sink::Teams.payload("Daily update", SUMMARY);
+        let card = &teams["attachments"][0]["content"];
+        assert_eq!(card["type"], "AdaptiveCard");
+        assert_eq!(card["body"][1]["text"], to_teams(SUMMARY));
+
+        let webhook = Sink::Webhook(None).payload("Daily update", SUMMARY);
+        assert_eq!(webhook["markdown"], SUMMARY);
+        assert_eq!(webhook["text"], to_plain(SUMMARY));
+    }
+}
@@ -0,0 +1,29 @@
+use anyhow::{bail, Result};
+use std::sync::OnceLock;
+
+static ACTIVE: OnceLock<Option<String>> = OnceLock::new();
+
+pub fn init(name: Option<String>) -> Result<()> {
+    let name = name.filter(|n| !n.is_empty() && n != "default");
+    if let Some(name) = &name
+        && !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!(
+            "Invalid profile name {:?}, use letters, digits, '-' and '_'.",
+            name
+        );
+    }
+    let _ = ACTIVE.set(name);
+    Ok(())
+}
+
+pub fn active() -> Option<&'static str> {
+    ACTIVE.get().and_then(|name| name.as_deref())
+}
+
+pub fn config_name() -> String {
+    match active() {
+        Some(name) => format!("config-{}", name),
+        None => "config".to_string(),
+    }
+}
@@ -0,0 +1,221 @@
+use crate::{
+    ai::{self, CachePolicy, ChatMessage},
+    settings::AppConfig,
+};
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+const HELP: &str = "Type an instruction (e.g. \"shorter\", \"translate to German\") to refine the summary.
+  <enter> or :accept   accept the current version and keep it in the history
+  :save <file>         write the current version to a file
+  :copy                copy the current version to the clipboard
+  :quit                discard and exit (also on end of input)";
+
+pub fn print_summary(summary: &str) {
+    println!("\n{}", "--- Daily Report ---".bold().green());
+    println!("{}", summary);
+    println!("{}", "-----------------".green());
+}
+
+#[derive(Debug, PartialEq)]
+enum Input<'a> {
+    Accept,
+    Quit,
+    Help,
+    Copy,
+    Save(&'a str),
+    Unknown,
+    Refine(&'a str),
+}
+
+fn parse(line: &str) -> Input<'_> {
+    let input = line.trim();
+    match input {
+        "" | ":accept" | ":a" => Input::Accept,
+        ":quit" | ":q" => Input::Quit,
+        ":help" | ":h" => Input::Help,
+        ":copy" | ":c" => Input::Copy,
+        _ if input == ":save" || input.starts_with(":save ") => Input::Save(input[":save".len()..].trim()),
+        _ if input.starts_with(':') => Input::Unknown,
+        instruction => Input::Refine(instruction),
+    }
+}
+
+pub fn run(
+    config: &AppConfig,
+    messages: Vec<ChatMessage>,
+    summary: String,
+    policy: CachePolicy,
+) -> Result<Option<String>> {
+    println!("{}", HELP.dimmed());
+
+    let stdin = io::stdin();
+    refine(messages, summary, stdin.lock().lines(), |messages| {
+        println!("AI refining summary... ({})", config.ai.model);
+        ai::chat(config, messages, policy)
+    })
+}
+
+fn refine(
+    mut messages: Vec<ChatMessage>,
+    summary: String,
+    mut lines: impl Iterator<Item = io::Result<String>>,
+    mut chat: impl FnMut(&[ChatMessage]) -> Result<String>,
+) -> Result<Option<String>> {
+    let mut current = summary;
+    messages.push(ChatMessage::assistant(current.clone()));
+
+    loop {
+        print!("{} ", "refine>".cyan().bold());
+        io::stdout().flush()?;
+
+        let line = match lines.next() {
+            None => return Ok(None),
+            Some(line) => line?,
+        };
+
+        match parse(&line) {
+            Input::Accept => return Ok(Some(current)),
+            Input::Quit => return Ok(None),
+            Input::Help => println!("{}", HELP),
+            Input::Copy => match copy_to_clipboard(&current) {
+                Ok(()) => println!("Copied to clipboard."),
+                Err(e) => eprintln!("Failed to copy: {}", e),
+            },
+            Input::Save("") => println!("Usage: :save <file>"),
+            Input::Save(target) => {
+                let path = PathBuf::from(target);
+                match fs::write(&path, format!("{}\n", current)) {
+                    Ok(()) => println!("Saved to {:?}.", path),
+                    Err(e) => eprintln!("Failed to save: {}", e),
+                }
+            }
+            Input::Unknown => println!("Unknown command. Type :help."),
+            Input::Refine(instruction) => {
+                messages.push(ChatMessage::user(instruction));
+                match chat(&messages) {
+                    Ok(refined) => {
+                        messages.push(ChatMessage::assistant(refined.clone()));
+                        current = refined;
+                        print_summary(&current);
+                    }
+                    Err(e) => {
+                        messages.pop();
+                        eprintln!("Failed to refine report: {}", e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn copy_to_clipboard(text: &str) -> Result<()> {
+    let candidates: &[(&str, &[&str])] = if cfg!(target_os = "macos") {
+        &[("pbcopy", &[])]
+    } else if cfg!(windows) {
+        &[("clip", &[])]
+    } else {
+        &[
+            ("wl-copy", &[]),
+            ("xclip", &["-selection", "clipboard"]),
+            ("xsel", &["--clipboard", "--input"]),
+        ]
+    };
+
+    for (program, program_args) in candidates {
+        let child = Command::new(program)
+            .args(*program_args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+
+        let Ok(mut child) = child else {
+            continue;
+        };
+
+        child
+            .stdin
+            .take()
+            .context("Clipboard stdin is not available")?
+            .write_all(text.as_bytes())?;
+
+        if child.wait()?.success() {
+            return Ok(());
+        }
+    }
+    bail!("No clipboard tool found (tried pbcopy, clip, wl-copy, xclip, xsel).")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use tempfile::TempDir;
+
+    fn lines(input: &[&str]) -> impl Iterator<Item = io::Result<String>> {
+        input.iter().map(|line| Ok(line.to_string())).collect::<Vec<_>>().into_iter()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse(""), Input::Accept);
+        assert_eq!(parse("  :a "), Input::Accept);
+        assert_eq!(parse(":q"), Input::Quit);
+        assert_eq!(parse(":help"), Input::Help);
+        assert_eq!(parse(":c"), Input::Copy);
+        assert_eq!(parse(":save  report.md "), Input::Save("report.md"));
+        assert_eq!(parse(":save"), Input::Save(""));
+        assert_eq!(parse(":saved"), Input::Unknown);
+        assert_eq!(parse(":bogus"), Input::Unknown);
+        assert_eq!(parse(" shorter "), Input::Refine("shorter"));
+    }
+
+    #[test]
+    fn test_refine_keeps_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("report.md");
+        let save = format!(":save {}", file.display());
+        let mut seen = Vec::new();
+        let accepted = refine(
+            vec![ChatMessage::system("prompt")],
+            "draft".to_string(),
+            lines(&["shorter", "fail", "in German", &save, ""]),
+            |messages| {
+                let roles: Vec<&str> = messages.iter().map(|m| m.role.as_str()).collect();
+                seen.push((roles.join(","), messages.last().unwrap().content.clone()));
+                match messages.last().unwrap().content.as_str() {
+                    "fail" => Err(anyhow!("timeout")),
+                    instruction => Ok(format!("v{} {}", messages.len(), instruction)),
+                }
+            },
+        )
+        .unwrap();
+
+        assert_eq!(accepted.as_deref(), Some("v5 in German"));
+        assert_eq!(
+            seen,
+            vec![
+                ("system,assistant,user".to_string(), "shorter".to_string()),
+                ("system,assistant,user,assistant,user".to_string(), "fail".to_string()),
+                ("system,assistant,user,assistant,user".to_string(), "in German".to_string()),
+            ]
+        );
+        assert_eq!(fs::read_to_string(file).unwrap(), "v5 in German\n");
+    }
+
+    #[test]
+    fn test_refine_discards_on_quit_and_end_of_input() {
+        let chat = |_: &[ChatMessage]| -> Result<String> { Ok("refined".to_string()) };
+        assert!(refine(Vec::new(), "draft".to_string(), lines(&["shorter", ":q"]), chat).unwrap().is_none());
+        assert!(refine(Vec::new(), "draft".to_string(), lines(&["shorter"]), chat).unwrap().is_none());
+        assert_eq!(
+            refine(Vec::new(), "draft".to_string(), lines(&[":accept"]), chat).unwrap().as_deref(),
+            Some("draft")
+        );
+    }
+}
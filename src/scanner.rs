@@ -1,9 +1,137 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use git2::Repository;
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use rayon::prelude::*;
+use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use walkdir::{DirEntry, WalkDir};
 
+pub const IGNORE_FILE: &str = ".yestergitignore";
+
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    pub ignore: Vec<String>,
+    pub max_depth: Option<usize>,
+    pub follow_links: bool,
+    pub submodules: bool,
+}
+
+pub struct ScanResult {
+    pub repos: Vec<PathBuf>,
+    pub worktrees: Vec<(PathBuf, PathBuf)>,
+    pub containers: Vec<PathBuf>,
+    pub dirs_visited: usize,
+}
+
+enum Detected {
+    Repo { submodules: Vec<PathBuf> },
+    Worktree { main: PathBuf },
+    Bare,
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn looks_bare(dir: &Path) -> bool {
+    dir.join("HEAD").is_file() && dir.join("objects").is_dir() && dir.join("refs").is_dir()
+}
+
+fn detect(dir: &Path) -> Option<Detected> {
+    if dir.join(".git").exists() {
+        let repo = Repository::open(dir).ok()?;
+        if repo.is_worktree() {
+            let common = repo.commondir();
+            let main = match common.file_name() {
+                Some(name) if name == ".git" => common.parent()?,
+                _ => common,
+            };
+            return Some(Detected::Worktree { main: canonical(main) });
+        }
+
+        let workdir = repo.workdir()?.to_path_buf();
+        let submodules = repo
+            .submodules()
+            .map(|subs| subs.iter().map(|sub| canonical(&workdir.join(sub.path()))).collect())
+            .unwrap_or_default();
+        return Some(Detected::Repo { submodules });
+    }
+
+    if looks_bare(dir) && Repository::open_bare(dir).is_ok_and(|repo| repo.is_bare()) {
+        return Some(Detected::Bare);
+    }
+    None
+}
+
+struct IgnoreRules {
+    names: GlobSet,
+    paths: GlobSet,
+}
+
+impl IgnoreRules {
+    fn new(patterns: &[String]) -> Result<Self> {
+        let mut names = GlobSetBuilder::new();
+        let mut paths = GlobSetBuilder::new();
+
+        for pattern in patterns {
+            let pattern = pattern.trim().trim_end_matches('/');
+            if pattern.is_empty() {
+                continue;
+            }
+            let glob = Glob::new(pattern.trim_start_matches('/'))
+                .with_context(|| format!("Invalid ignore pattern {:?}", pattern))?;
+            if pattern.contains('/') {
+                paths.add(glob);
+            } else {
+                names.add(glob);
+            }
+        }
+
+        Ok(Self {
+            names: names.build()?,
+            paths: paths.build()?,
+        })
+    }
+
+    fn is_ignored(&self, path: &Path, root: &Path) -> bool {
+        if path
+            .file_name()
+            .is_some_and(|name| self.names.is_match(name))
+        {
+            return true;
+        }
+        path.strip_prefix(root)
+            .is_ok_and(|relative| self.paths.is_match(relative))
+    }
+}
+
+struct Progress {
+    dirs: AtomicUsize,
+    repos: AtomicUsize,
+    visible: bool,
+}
+
+impl Progress {
+    fn dir_visited(&self) {
+        let dirs = self.dirs.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.visible && dirs.is_multiple_of(500) {
+            eprint!(
+                "\rScanning... {} directories visited, {} repos found",
+                dirs,
+                self.repos.load(Ordering::Relaxed)
+            );
+        }
+    }
+
+    fn repo_found(&self) {
+        self.repos.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
 fn is_hidden(entry: &DirEntry) -> bool {
     entry
         .file_name()
@@ -12,14 +140,44 @@ fn is_hidden(entry: &DirEntry) -> bool {
         .unwrap_or(false)
 }
 
-fn scan_worker(root: PathBuf) -> Vec<PathBuf> {
-    let mut repos = Vec::new();
+pub fn read_ignore_file(path: &Path) -> Vec<String> {
+    fs::read_to_string(path)
+        .map(|content| {
+            content
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+struct ScanContext<'a> {
+    root: &'a Path,
+    options: &'a ScanOptions,
+    rules: IgnoreRules,
+    visited: Mutex<HashSet<PathBuf>>,
+    skip: HashSet<PathBuf>,
+    in_repo: bool,
+    progress: Progress,
+}
+
+fn scan_worker(root: PathBuf, ctx: &ScanContext) -> Vec<Found> {
+    let (options, progress) = (ctx.options, &ctx.progress);
+    let mut found = Vec::new();
+    let mut skip = ctx.skip.clone();
+    let mut repo_stack: Vec<PathBuf> = Vec::new();
 
     if !root.exists() {
-        return repos;
+        return found;
     }
 
-    let mut it = WalkDir::new(&root).into_iter();
+    let mut walker = WalkDir::new(&root).follow_links(options.follow_links);
+    if let Some(max_depth) = options.max_depth {
+        walker = walker.max_depth(max_depth.saturating_sub(1));
+    }
+    let mut it = walker.into_iter();
 
     loop {
         let entry = match it.next() {
@@ -28,39 +186,310 @@ fn scan_worker(root: PathBuf) -> Vec<PathBuf> {
             Some(Ok(e)) => e,
         };
 
-        if is_hidden(&entry) {
-            if entry.file_type().is_dir() {
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+
+        if is_hidden(&entry) || ctx.rules.is_ignored(entry.path(), ctx.root) {
+            it.skip_current_dir();
+            continue;
+        }
+
+        if options.follow_links {
+            let canonical = fs::canonicalize(entry.path()).unwrap_or_else(|_| entry.path().to_path_buf());
+            if !ctx.visited.lock().unwrap().insert(canonical) {
                 it.skip_current_dir();
+                continue;
             }
-            continue;
         }
 
-        if entry.file_type().is_dir() && entry.path().join(".git").exists() {
-            repos.push(entry.path().to_path_buf());
+        progress.dir_visited();
+
+        if !options.submodules && !skip.is_empty() && skip.contains(&canonical(entry.path())) {
             it.skip_current_dir();
+            continue;
+        }
+
+        while repo_stack.last().is_some_and(|repo| !entry.path().starts_with(repo)) {
+            repo_stack.pop();
+        }
+
+        match detect(entry.path()) {
+            Some(Detected::Repo { submodules }) => {
+                progress.repo_found();
+                skip.extend(submodules);
+                found.push(Found::Repo(entry.path().to_path_buf()));
+                repo_stack.push(entry.path().to_path_buf());
+            }
+            Some(Detected::Worktree { main }) => {
+                found.push(Found::Worktree(entry.path().to_path_buf(), main));
+                repo_stack.push(entry.path().to_path_buf());
+            }
+            Some(Detected::Bare) => {
+                progress.repo_found();
+                found.push(Found::Repo(entry.path().to_path_buf()));
+                it.skip_current_dir();
+            }
+            None if !ctx.in_repo && repo_stack.is_empty() => {
+                found.push(Found::Container(entry.path().to_path_buf()));
+            }
+            None => {}
         }
     }
-    repos
+    found
 }
 
-pub fn scan_repositories(root: PathBuf) -> Result<Vec<PathBuf>> {
-    if root.join(".git").exists() {
-        if let Ok(abs) = fs::canonicalize(&root) {
-            return Ok(vec![abs]);
+enum Found {
+    Repo(PathBuf),
+    Worktree(PathBuf, PathBuf),
+    Container(PathBuf),
+}
+
+pub fn scan_repositories(root: PathBuf, options: &ScanOptions) -> Result<ScanResult> {
+    scan_subtree(&root, root.clone(), options)
+}
+
+pub fn scan_subtree(root: &Path, start: PathBuf, options: &ScanOptions) -> Result<ScanResult> {
+    let mut patterns = options.ignore.clone();
+    patterns.extend(read_ignore_file(&root.join(IGNORE_FILE)));
+    let depth = start.strip_prefix(root).map_or(0, |rest| rest.components().count());
+    let options = &ScanOptions {
+        max_depth: options.max_depth.map(|max| max.saturating_sub(depth)),
+        ..options.clone()
+    };
+
+    let mut found = Vec::new();
+    let mut skip = HashSet::new();
+    let detected = detect(&start);
+    let root_is_bare = matches!(detected, Some(Detected::Bare));
+    let in_repo = detected.is_some();
+    match detected {
+        Some(Detected::Repo { submodules }) => {
+            skip.extend(submodules);
+            found.push(Found::Repo(start.clone()));
+        }
+        Some(Detected::Worktree { main }) => found.push(Found::Worktree(start.clone(), main)),
+        Some(Detected::Bare) => found.push(Found::Repo(start.clone())),
+        None => found.push(Found::Container(start.clone())),
+    }
+
+    let ctx = ScanContext {
+        root,
+        options,
+        rules: IgnoreRules::new(&patterns)?,
+        visited: Mutex::new(HashSet::new()),
+        skip,
+        in_repo,
+        progress: Progress {
+            dirs: AtomicUsize::new(1),
+            repos: AtomicUsize::new(0),
+            visible: std::io::stderr().is_terminal(),
+        },
+    };
+
+    let entries: Vec<PathBuf> = if options.max_depth != Some(0) && !root_is_bare {
+        fs::read_dir(&start)?
+            .filter_map(|e| e.ok())
+            .filter(|e| {
+                let is_link = e.file_type().is_ok_and(|t| t.is_symlink());
+                (options.follow_links || !is_link) && e.path().is_dir()
+            })
+            .map(|e| e.path())
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    found.par_extend(
+        entries
+            .par_iter()
+            .map(|path| scan_worker(path.clone(), &ctx))
+            .flatten(),
+    );
+
+    if ctx.progress.visible {
+        eprint!("\r\x1b[2K");
+    }
+
+    let mut seen = HashSet::new();
+    let mut repos = Vec::new();
+    let mut worktrees = Vec::new();
+    let mut containers = Vec::new();
+    for item in found {
+        let (repo, worktree) = match item {
+            Found::Repo(path) => (canonical(&path), None),
+            Found::Worktree(path, main) => (main, Some(canonical(&path))),
+            Found::Container(path) => {
+                containers.push(path);
+                continue;
+            }
+        };
+        if let Some(worktree) = worktree {
+            worktrees.push((worktree, repo.clone()));
         }
-        return Ok(vec![root]);
+        if seen.insert(repo.clone()) {
+            repos.push(repo);
+        }
+    }
+
+    Ok(ScanResult {
+        repos,
+        worktrees,
+        containers,
+        dirs_visited: ctx.progress.dirs.load(Ordering::Relaxed),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn make_repo(path: &Path) -> Repository {
+        fs::create_dir_all(path).unwrap();
+        Repository::init(path).unwrap()
+    }
+
+    fn commit_file(repo: &Repository, name: &str) {
+        let workdir = repo.workdir().unwrap();
+        fs::write(workdir.join(name), "content").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(name)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let parents: Vec<git2::Commit> = repo
+            .head()
+            .ok()
+            .and_then(|h| h.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, "commit", &tree, &parents)
+            .unwrap();
+    }
+
+    fn names(result: &ScanResult) -> Vec<String> {
+        let mut names: Vec<String> = result
+            .repos
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_ignore_rules_and_ignore_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        make_repo(&root.join("work/api"));
+        make_repo(&root.join("work/node_modules/dep"));
+        make_repo(&root.join("archive/old"));
+        make_repo(&root.join("vendor/lib"));
+        fs::write(root.join(IGNORE_FILE), "# comment\narchive/\n").unwrap();
+
+        let options = ScanOptions {
+            ignore: vec!["node_modules".to_string(), "/vendor".to_string()],
+            ..Default::default()
+        };
+        let result = scan_repositories(root.to_path_buf(), &options).unwrap();
+        assert_eq!(names(&result), vec!["api"]);
+        assert!(result.dirs_visited >= 3);
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        make_repo(&root.join("shallow"));
+        make_repo(&root.join("a/b/deep"));
+
+        let options = ScanOptions {
+            max_depth: Some(2),
+            ..Default::default()
+        };
+        let result = scan_repositories(root.to_path_buf(), &options).unwrap();
+        assert_eq!(names(&result), vec!["shallow"]);
+
+        let result = scan_repositories(root.to_path_buf(), &ScanOptions::default()).unwrap();
+        assert_eq!(names(&result), vec!["deep", "shallow"]);
     }
 
-    let entries: Vec<PathBuf> = fs::read_dir(&root)?
-        .filter_map(|e| e.ok().map(|e| e.path()))
-        .filter(|p| p.is_dir())
-        .collect();
+    #[cfg(unix)]
+    #[test]
+    fn test_follow_links_with_loop() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("root");
+        let outside = temp_dir.path().join("outside");
+        make_repo(&outside.join("linked"));
+        fs::create_dir_all(root.join("dir")).unwrap();
+        std::os::unix::fs::symlink(&outside, root.join("dir/link")).unwrap();
+        std::os::unix::fs::symlink(&root, root.join("dir/loop")).unwrap();
+        std::os::unix::fs::symlink(&outside, root.join("top-link")).unwrap();
 
-    let repos: Vec<PathBuf> = entries
-        .par_iter()
-        .map(|path| scan_worker(path.clone()))
-        .flatten()
-        .collect();
+        let result = scan_repositories(root.clone(), &ScanOptions::default()).unwrap();
+        assert!(result.repos.is_empty());
 
-    Ok(repos)
+        let options = ScanOptions {
+            follow_links: true,
+            ..Default::default()
+        };
+        let result = scan_repositories(root, &options).unwrap();
+        assert_eq!(names(&result), vec!["linked"]);
+    }
+
+    #[test]
+    fn test_invalid_git_entries_are_not_repos() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("fake-dir/.git")).unwrap();
+        fs::create_dir_all(root.join("fake-file")).unwrap();
+        fs::write(root.join("fake-file/.git"), "gitdir: /nowhere").unwrap();
+        make_repo(&root.join("real"));
+
+        let result = scan_repositories(root.to_path_buf(), &ScanOptions::default()).unwrap();
+        assert_eq!(names(&result), vec!["real"]);
+    }
+
+    #[test]
+    fn test_nested_worktrees_and_bare() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let main = make_repo(&root.join("main"));
+        commit_file(&main, "README.md");
+        make_repo(&root.join("main/tools/nested"));
+        main.worktree("feature", &root.join("feature-wt"), None).unwrap();
+        Repository::init_bare(root.join("mirror.git")).unwrap();
+
+        let result = scan_repositories(root.to_path_buf(), &ScanOptions::default()).unwrap();
+        assert_eq!(names(&result), vec!["main", "mirror.git", "nested"]);
+        assert_eq!(result.worktrees.len(), 1);
+        assert_eq!(result.worktrees[0].1, canonical(&root.join("main")));
+    }
+
+    #[test]
+    fn test_submodules_are_optional() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("root");
+        let dep = make_repo(&temp_dir.path().join("dep"));
+        commit_file(&dep, "lib.rs");
+
+        let app = make_repo(&root.join("app"));
+        commit_file(&app, "main.rs");
+        let url = temp_dir.path().join("dep").to_string_lossy().to_string();
+        let mut sub = app.submodule(&url, Path::new("libs/dep"), true).unwrap();
+        sub.clone(None).unwrap();
+        sub.add_finalize().unwrap();
+
+        let result = scan_repositories(root.clone(), &ScanOptions::default()).unwrap();
+        assert_eq!(names(&result), vec!["app"]);
+
+        let options = ScanOptions {
+            submodules: true,
+            ..Default::default()
+        };
+        let result = scan_repositories(root, &options).unwrap();
+        assert_eq!(names(&result), vec!["app", "dep"]);
+    }
 }
@@ -1,19 +1,321 @@
+use crate::profile;
+use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const APP_NAME: &str = "yestergit";
+const ENV_PREFIX: &str = "YESTERGIT_";
+pub const SECRET_KEYS: &[&str] = &[
+    "ai.api_key",
+    "post.slack",
+    "post.teams",
+    "post.mattermost",
+    "post.webhook",
+    "email.password",
+];
+
+pub fn load_file() -> Result<AppConfig> {
+    Ok(confy::load(APP_NAME, profile::config_name().as_str())?)
+}
+
+pub fn load() -> Result<AppConfig> {
+    apply_env(load_file()?, env::vars())
+}
+
+pub fn store(config: &AppConfig) -> Result<()> {
+    Ok(confy::store(APP_NAME, profile::config_name().as_str(), config)?)
+}
+
+pub fn config_path() -> Result<PathBuf> {
+    Ok(confy::get_configuration_file_path(APP_NAME, profile::config_name().as_str())?)
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AppConfig {
     pub ai: AiConfig,
+    #[serde(default)]
+    pub cache: CacheConfig,
+    #[serde(default)]
+    pub team: TeamConfig,
+    #[serde(default)]
+    pub hours: HoursConfig,
+    #[serde(default)]
+    pub scan: ScanConfig,
+    #[serde(default)]
+    pub identity: IdentityConfig,
+    #[serde(default)]
+    pub post: PostConfig,
+    #[serde(default)]
+    pub email: EmailConfig,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Serialize, Deserialize)]
 pub struct AiConfig {
     pub api_url: String,
     pub model: String,
     pub api_key: String,
+    #[serde(default)]
+    pub api_key_cmd: String,
+    #[serde(default)]
+    pub api_key_file: String,
+    #[serde(default)]
+    pub api_key_env: String,
     pub language: String,
     pub prompt: String,
 }
 
+impl AiConfig {
+    pub fn resolve_api_key(&self) -> Result<String> {
+        if !self.api_key_cmd.is_empty() {
+            return key_from_command(&self.api_key_cmd);
+        }
+        if !self.api_key_file.is_empty() {
+            return key_from_file(Path::new(&self.api_key_file));
+        }
+        if !self.api_key_env.is_empty() {
+            return env::var(&self.api_key_env)
+                .with_context(|| format!("ai.api_key_env points to {}, which is not set.", self.api_key_env));
+        }
+        Ok(self.api_key.clone())
+    }
+}
+
+fn key_from_command(command: &str) -> Result<String> {
+    let output = if cfg!(windows) {
+        Command::new("cmd").args(["/C", command]).output()
+    } else {
+        Command::new("sh").args(["-c", command]).output()
+    }
+    .with_context(|| format!("Could not run ai.api_key_cmd {:?}.", command))?;
+
+    if !output.status.success() {
+        bail!(
+            "ai.api_key_cmd {:?} failed ({}): {}",
+            command,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let key = String::from_utf8(output.stdout).context("ai.api_key_cmd printed invalid UTF-8.")?;
+    let key = key.trim();
+    if key.is_empty() {
+        bail!("ai.api_key_cmd {:?} printed nothing.", command);
+    }
+    Ok(key.to_string())
+}
+
+fn key_from_file(path: &Path) -> Result<String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(path)
+            .with_context(|| format!("Could not read ai.api_key_file {:?}.", path))?
+            .permissions()
+            .mode();
+        if mode & 0o077 != 0 {
+            bail!(
+                "ai.api_key_file {:?} can be read by other users (mode {:o}). Run `chmod 600 {}`.",
+                path,
+                mode & 0o777,
+                path.display()
+            );
+        }
+    }
+    let key = fs::read_to_string(path).with_context(|| format!("Could not read ai.api_key_file {:?}.", path))?;
+    let key = key.trim();
+    if key.is_empty() {
+        bail!("ai.api_key_file {:?} is empty.", path);
+    }
+    Ok(key.to_string())
+}
+
+pub fn is_world_readable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::metadata(path).is_ok_and(|m| m.permissions().mode() & 0o004 != 0)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+impl fmt::Debug for AiConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AiConfig")
+            .field("api_url", &self.api_url)
+            .field("model", &self.model)
+            .field("api_key", &mask(&self.api_key))
+            .field("api_key_cmd", &self.api_key_cmd)
+            .field("api_key_file", &self.api_key_file)
+            .field("api_key_env", &self.api_key_env)
+            .field("language", &self.language)
+            .field("prompt", &self.prompt)
+            .finish()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    pub enabled: bool,
+    pub ttl_hours: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl_hours: 24,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HoursConfig {
+    pub max_gap_minutes: u64,
+    pub first_commit_minutes: u64,
+}
+
+impl Default for HoursConfig {
+    fn default() -> Self {
+        Self {
+            max_gap_minutes: 120,
+            first_commit_minutes: 120,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdentityConfig {
+    pub authors: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PostConfig {
+    pub slack: String,
+    pub teams: String,
+    pub mattermost: String,
+    pub webhook: String,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    #[default]
+    Starttls,
+    Tls,
+    None,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmailConfig {
+    pub host: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    pub username: String,
+    pub password: String,
+    pub from: String,
+    pub to: Vec<String>,
+    pub cc: Vec<String>,
+}
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        Self {
+            host: String::new(),
+            port: 587,
+            security: SmtpSecurity::default(),
+            username: String::new(),
+            password: String::new(),
+            from: String::new(),
+            to: Vec::new(),
+            cc: Vec::new(),
+        }
+    }
+}
+
+impl fmt::Debug for EmailConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmailConfig")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("security", &self.security)
+            .field("username", &self.username)
+            .field("password", &mask(&self.password))
+            .field("from", &self.from)
+            .field("to", &self.to)
+            .field("cc", &self.cc)
+            .finish()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanConfig {
+    pub ignore: Vec<String>,
+    pub max_depth: Option<usize>,
+    pub follow_links: bool,
+    pub submodules: bool,
+    pub rescan_interval_minutes: u64,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            ignore: ["node_modules", "target", "vendor", "venv", "__pycache__", "dist", "build"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            max_depth: None,
+            follow_links: false,
+            submodules: false,
+            rescan_interval_minutes: 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamMember {
+    pub name: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TeamConfig {
+    pub members: Vec<TeamMember>,
+    pub prompt: String,
+}
+
+impl Default for TeamConfig {
+    fn default() -> Self {
+        Self {
+            members: Vec::new(),
+            prompt: r#"Act as a team lead preparing notes for a Daily Scrum meeting.
+Based on the commit logs below, write a short summary in {LANGUAGE} of what {NAME} worked on.
+Use the third person and mention the project name when describing the work.
+Do NOT use bullet points or commit hashes. Keep it to two or three sentences.
+
+Commit Logs:
+{LOGS}"#
+                .to_string(),
+        }
+    }
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
@@ -21,6 +323,9 @@ impl Default for AppConfig {
                 api_url: "http://localhost:11434/v1/chat/completions".to_string(),
                 model: "llama3".to_string(),
                 api_key: "".to_string(),
+                api_key_cmd: String::new(),
+                api_key_file: String::new(),
+                api_key_env: String::new(),
                 language: "English".to_string(),
                 prompt: r#"Act as a software developer giving a quick verbal update at a Daily Scrum meeting.
 Based on the commit logs below, draft a ** short, conversational summary**  in {LANGUAGE}.
@@ -31,6 +336,325 @@ Do NOT use bullet points, commit hashes, or technical jargon. Keep it casual and
 Commit Logs:
 {LOGS}"#.to_string(),
             },
+            cache: CacheConfig::default(),
+            team: TeamConfig::default(),
+            hours: HoursConfig::default(),
+            scan: ScanConfig::default(),
+            identity: IdentityConfig::default(),
+            post: PostConfig::default(),
+            email: EmailConfig::default(),
+        }
+    }
+}
+
+pub fn mask(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    match chars.len() {
+        0 => String::new(),
+        1..=8 => "********".to_string(),
+        n => format!(
+            "{}…{}",
+            chars[..3].iter().collect::<String>(),
+            chars[n - 4..].iter().collect::<String>()
+        ),
+    }
+}
+
+pub fn env_name(key: &str) -> String {
+    format!("{}{}", ENV_PREFIX, key.replace('.', "_").to_uppercase())
+}
+
+fn flatten(value: &Value, prefix: &str, out: &mut Vec<(String, Value)>) {
+    match value {
+        Value::Object(map) => {
+            for (name, child) in map {
+                let key = if prefix.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{}.{}", prefix, name)
+                };
+                flatten(child, &key, out);
+            }
+        }
+        leaf => out.push((prefix.to_string(), leaf.clone())),
+    }
+}
+
+pub fn entries(config: &AppConfig) -> Result<Vec<(String, Value)>> {
+    let mut out = Vec::new();
+    flatten(&serde_json::to_value(config)?, "", &mut out);
+    Ok(out)
+}
+
+fn lookup<'a>(value: &'a mut Value, key: &str) -> Result<&'a mut Value> {
+    let mut node = value;
+    for part in key.split('.') {
+        node = node
+            .get_mut(part)
+            .with_context(|| format!("Unknown setting {:?}. Run `yestergit config get` to list them.", key))?;
+    }
+    if node.is_object() {
+        bail!("{:?} is a section, pick one of its keys.", key);
+    }
+    Ok(node)
+}
+
+pub fn stored_secrets(config: &AppConfig) -> Result<Vec<&'static str>> {
+    let value = serde_json::to_value(config)?;
+    Ok(SECRET_KEYS
+        .iter()
+        .copied()
+        .filter(|key| {
+            key.split('.')
+                .try_fold(&value, |node, part| node.get(part))
+                .and_then(Value::as_str)
+                .is_some_and(|secret| !secret.is_empty())
+        })
+        .collect())
+}
+
+pub fn strip_secrets(value: &mut Value) {
+    for key in SECRET_KEYS {
+        if let Some(Value::String(secret)) = key.split('.').try_fold(&mut *value, |node, part| node.get_mut(part)) {
+            secret.clear();
         }
     }
 }
+
+pub fn fill_missing_secrets(config: AppConfig, from: &AppConfig) -> Result<AppConfig> {
+    let mut config = config;
+    for key in SECRET_KEYS {
+        if get(&config, key)?.as_str().is_some_and(str::is_empty) {
+            let value = get(from, key)?;
+            config = with_value(&config, key, |_| Ok(value))?;
+        }
+    }
+    Ok(config)
+}
+
+pub fn get(config: &AppConfig, key: &str) -> Result<Value> {
+    let mut value = serde_json::to_value(config)?;
+    Ok(lookup(&mut value, key)?.clone())
+}
+
+fn parse_value(current: &Value, key: &str, raw: &str) -> Result<Value> {
+    let parsed = match current {
+        Value::String(_) => Value::String(raw.to_string()),
+        Value::Bool(_) => Value::Bool(
+            raw.parse()
+                .with_context(|| format!("{} expects true or false, got {:?}.", key, raw))?,
+        ),
+        Value::Number(_) => serde_json::from_str::<serde_json::Number>(raw)
+            .map(Value::Number)
+            .with_context(|| format!("{} expects a number, got {:?}.", key, raw))?,
+        Value::Array(_) if !raw.trim_start().starts_with('[') => Value::Array(
+            raw.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| Value::String(item.to_string()))
+                .collect(),
+        ),
+        _ => serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string())),
+    };
+    Ok(parsed)
+}
+
+fn with_value(config: &AppConfig, key: &str, make: impl FnOnce(&Value) -> Result<Value>) -> Result<AppConfig> {
+    let mut value = serde_json::to_value(config)?;
+    let node = lookup(&mut value, key)?;
+    *node = make(node)?;
+    serde_json::from_value(value).with_context(|| format!("Invalid value for {}.", key))
+}
+
+pub fn set(config: &AppConfig, key: &str, raw: &str) -> Result<AppConfig> {
+    let updated = with_value(config, key, |current| parse_value(current, key, raw))?;
+    validate_key(&updated, key)?;
+    Ok(updated)
+}
+
+pub fn unset(config: &AppConfig, key: &str) -> Result<AppConfig> {
+    let default = get(&AppConfig::default(), key)?;
+    with_value(config, key, |_| Ok(default))
+}
+
+pub fn apply_env(config: AppConfig, vars: impl IntoIterator<Item = (String, String)>) -> Result<AppConfig> {
+    let overrides: Vec<(String, String)> = vars
+        .into_iter()
+        .filter(|(name, _)| name.starts_with(ENV_PREFIX))
+        .collect();
+    if overrides.is_empty() {
+        return Ok(config);
+    }
+
+    let mut config = config;
+    let mut overridden = Vec::new();
+    for (key, _) in entries(&config)? {
+        let name = env_name(&key);
+        if let Some((_, raw)) = overrides.iter().find(|(n, _)| *n == name) {
+            config = with_value(&config, &key, |current| parse_value(current, &key, raw))
+                .with_context(|| format!("Invalid value in {}.", name))?;
+            overridden.push((key, name));
+        }
+    }
+    for (key, name) in overridden {
+        validate_key(&config, &key).with_context(|| format!("Invalid value in {}.", name))?;
+    }
+    Ok(config)
+}
+
+pub fn validate(config: &AppConfig) -> Result<()> {
+    for (key, _) in entries(config)? {
+        validate_key(config, &key)?;
+    }
+    Ok(())
+}
+
+pub fn validate_key(config: &AppConfig, key: &str) -> Result<()> {
+    match key {
+        "ai.api_url" => {
+            let url = url::Url::parse(&config.ai.api_url)
+                .with_context(|| format!("ai.api_url {:?} is not a valid URL.", config.ai.api_url))?;
+            if !matches!(url.scheme(), "http" | "https") {
+                bail!("ai.api_url must start with http:// or https://.");
+            }
+        }
+        "ai.api_key_cmd" | "ai.api_key_file" | "ai.api_key_env" => {
+            let sources = [&config.ai.api_key_cmd, &config.ai.api_key_file, &config.ai.api_key_env];
+            if sources.iter().filter(|s| !s.is_empty()).count() > 1 {
+                bail!("Set only one of ai.api_key_cmd, ai.api_key_file and ai.api_key_env.");
+            }
+        }
+        "ai.language" if config.ai.language.trim().is_empty() => bail!("ai.language must not be empty."),
+        "ai.prompt" if !config.ai.prompt.contains("{LOGS}") => {
+            bail!("ai.prompt must contain {{LOGS}}, where the commit logs are inserted.")
+        }
+        "team.prompt" if !config.team.prompt.contains("{LOGS}") => {
+            bail!("team.prompt must contain {{LOGS}}, where the commit logs are inserted.")
+        }
+        "post.slack" | "post.teams" | "post.mattermost" | "post.webhook" => {
+            let hook = get(config, key)?;
+            let hook = hook.as_str().unwrap_or_default();
+            if !hook.is_empty() && !url::Url::parse(hook).is_ok_and(|u| matches!(u.scheme(), "http" | "https")) {
+                bail!("{} must be an http:// or https:// URL.", key);
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_set_unset() {
+        let config = AppConfig::default();
+        assert_eq!(get(&config, "hours.max_gap_minutes").unwrap(), 120);
+
+        let config = set(&config, "hours.max_gap_minutes", "90").unwrap();
+        let config = set(&config, "cache.enabled", "false").unwrap();
+        let config = set(&config, "scan.ignore", "node_modules, .venv").unwrap();
+        let config = set(&config, "scan.max_depth", "4").unwrap();
+        assert_eq!(config.hours.max_gap_minutes, 90);
+        assert!(!config.cache.enabled);
+        assert_eq!(config.scan.ignore, vec!["node_modules", ".venv"]);
+        assert_eq!(config.scan.max_depth, Some(4));
+
+        let config = unset(&config, "scan.max_depth").unwrap();
+        assert_eq!(config.scan.max_depth, None);
+
+        assert!(set(&config, "hours.max_gap_minutes", "soon").is_err());
+        assert!(set(&config, "ai", "x").is_err());
+        assert!(set(&config, "ai.nope", "x").is_err());
+    }
+
+    #[test]
+    fn test_validation() {
+        let config = AppConfig::default();
+        assert!(set(&config, "ai.api_url", "not a url").is_err());
+        assert!(set(&config, "ai.api_url", "ftp://example.com").is_err());
+        assert!(set(&config, "ai.language", " ").is_err());
+        assert!(set(&config, "ai.prompt", "Summarize my day").is_err());
+        assert!(set(&config, "ai.prompt", "Summarize: {LOGS}").is_ok());
+
+        let mut broken = AppConfig::default();
+        broken.ai.language = String::new();
+        assert!(validate(&broken).is_err());
+        let fixed = set(&broken, "ai.model", "gpt-4o").unwrap();
+        assert!(set(&fixed, "ai.language", "German").is_ok_and(|c| validate(&c).is_ok()));
+    }
+
+    #[test]
+    fn test_env_overrides_and_mask() {
+        let vars = vec![
+            ("YESTERGIT_AI_API_KEY".to_string(), "sk-secret-value-1234".to_string()),
+            ("YESTERGIT_CACHE_TTL_HOURS".to_string(), "6".to_string()),
+            ("YESTERGIT_PROFILE".to_string(), "work".to_string()),
+            ("HOME".to_string(), "/home/dev".to_string()),
+        ];
+        let config = apply_env(AppConfig::default(), vars).unwrap();
+        assert_eq!(config.ai.api_key, "sk-secret-value-1234");
+        assert_eq!(config.cache.ttl_hours, 6);
+
+        let debug = format!("{:?}", config);
+        assert!(!debug.contains("sk-secret-value-1234"));
+        assert_eq!(mask(&config.ai.api_key), "sk-…1234");
+        assert_eq!(mask("short"), "********");
+
+        let config = set(&config, "email.password", "hunter22").unwrap();
+        let config = set(&config, "post.slack", "https://hooks.slack.com/services/T/B/x").unwrap();
+        assert_eq!(stored_secrets(&config).unwrap(), vec!["ai.api_key", "post.slack", "email.password"]);
+        let mut value = serde_json::to_value(&config).unwrap();
+        strip_secrets(&mut value);
+        let stripped: AppConfig = serde_json::from_value(value).unwrap();
+        assert!(stored_secrets(&stripped).unwrap().is_empty());
+        assert_eq!(stripped.cache.ttl_hours, 6);
+        let restored = fill_missing_secrets(stripped, &config).unwrap();
+        assert_eq!(restored.email.password, "hunter22");
+
+        let bad = vec![("YESTERGIT_CACHE_TTL_HOURS".to_string(), "never".to_string())];
+        assert!(apply_env(AppConfig::default(), bad).is_err());
+        let bad = vec![("YESTERGIT_AI_API_URL".to_string(), "localhost:11434".to_string())];
+        assert!(apply_env(AppConfig::default(), bad).is_err());
+        let with_cmd = set(&AppConfig::default(), "ai.api_key_cmd", "pass show openai").unwrap();
+        let bad = vec![("YESTERGIT_AI_API_KEY_ENV".to_string(), "OPENAI_API_KEY".to_string())];
+        assert!(apply_env(with_cmd, bad).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_api_key_sources() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mut ai = AppConfig::default().ai;
+        ai.api_key = "plain".to_string();
+        assert_eq!(ai.resolve_api_key().unwrap(), "plain");
+
+        ai.api_key_cmd = "echo ' from-command '".to_string();
+        assert_eq!(ai.resolve_api_key().unwrap(), "from-command");
+        ai.api_key_cmd = "exit 3".to_string();
+        assert!(ai.resolve_api_key().is_err());
+        ai.api_key_cmd.clear();
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let file = temp_dir.path().join("key");
+        fs::write(&file, "from-file\n").unwrap();
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o644)).unwrap();
+        ai.api_key_file = file.to_string_lossy().to_string();
+        assert!(ai.resolve_api_key().is_err());
+        assert!(is_world_readable(&file));
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o600)).unwrap();
+        assert_eq!(ai.resolve_api_key().unwrap(), "from-file");
+        ai.api_key_file.clear();
+
+        ai.api_key_env = "YESTERGIT_TEST_UNSET_KEY_VAR".to_string();
+        assert!(ai.resolve_api_key().is_err());
+        ai.api_key_env = "PATH".to_string();
+        assert_eq!(ai.resolve_api_key().unwrap(), env::var("PATH").unwrap());
+
+        let config = AppConfig::default();
+        let config = set(&config, "ai.api_key_cmd", "pass show openai").unwrap();
+        assert!(set(&config, "ai.api_key_env", "OPENAI_API_KEY").is_err());
+    }
+}
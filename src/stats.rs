@@ -0,0 +1,210 @@
+use crate::git_ops::CommitLog;
+use chrono::{Datelike, Duration, NaiveDate, Timelike};
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const BAR_WIDTH: usize = 40;
+
+#[derive(Serialize, Debug, Default)]
+pub struct ActivityStats {
+    pub total_commits: usize,
+    pub per_repo: BTreeMap<String, usize>,
+    pub per_weekday: [usize; 7],
+    pub per_hour: [usize; 24],
+    pub insertions: usize,
+    pub deletions: usize,
+    pub top_files: Vec<(String, usize)>,
+    pub per_day: BTreeMap<NaiveDate, usize>,
+}
+
+pub fn compute(commits: &[(String, CommitLog)], top: usize) -> ActivityStats {
+    let mut stats = ActivityStats::default();
+    let mut files: HashMap<String, usize> = HashMap::new();
+
+    for (repo, c) in commits {
+        stats.total_commits += 1;
+        *stats.per_repo.entry(repo.clone()).or_default() += 1;
+        stats.per_weekday[c.date.weekday().num_days_from_monday() as usize] += 1;
+        stats.per_hour[c.date.hour() as usize] += 1;
+        *stats.per_day.entry(c.date.date_naive()).or_default() += 1;
+
+        if let Some(diff) = &c.stats {
+            stats.insertions += diff.insertions;
+            stats.deletions += diff.deletions;
+            for file in &diff.files {
+                *files.entry(format!("{}/{}", repo, file)).or_default() += 1;
+            }
+        }
+    }
+
+    let mut files: Vec<(String, usize)> = files.into_iter().collect();
+    files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    files.truncate(top);
+    stats.top_files = files;
+
+    stats
+}
+
+pub fn heatmap_start(today: NaiveDate, weeks: u32) -> NaiveDate {
+    let this_monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    this_monday - Duration::weeks(weeks.saturating_sub(1) as i64)
+}
+
+pub fn weeks_between(since: NaiveDate, today: NaiveDate) -> u32 {
+    ((heatmap_start(today, 1) - heatmap_start(since, 1)).num_days() / 7 + 1).max(1) as u32
+}
+
+pub fn csv_rows(stats: &ActivityStats) -> Vec<(&'static str, String, usize)> {
+    let mut rows = vec![
+        ("total", "commits".to_string(), stats.total_commits),
+        ("total", "insertions".to_string(), stats.insertions),
+        ("total", "deletions".to_string(), stats.deletions),
+    ];
+    rows.extend(stats.per_repo.iter().map(|(repo, count)| ("repo", repo.clone(), *count)));
+    rows.extend(WEEKDAYS.iter().zip(stats.per_weekday).map(|(day, count)| ("weekday", day.to_string(), count)));
+    rows.extend((0..24).zip(stats.per_hour).map(|(hour, count)| ("hour", format!("{:02}", hour), count)));
+    rows.extend(stats.top_files.iter().map(|(file, count)| ("file", file.clone(), *count)));
+    rows.extend(stats.per_day.iter().map(|(day, count)| ("day", day.to_string(), *count)));
+    rows
+}
+
+pub fn render_heatmap(per_day: &BTreeMap<NaiveDate, usize>, start: NaiveDate, today: NaiveDate) -> String {
+    let max = per_day
+        .range(start..=today)
+        .map(|(_, count)| *count)
+        .max()
+        .unwrap_or(0);
+    let weeks = ((today - start).num_days() / 7 + 1) as usize;
+
+    let mut out = String::new();
+    for (row, label) in WEEKDAYS.iter().enumerate() {
+        let label = if row % 2 == 0 { *label } else { "" };
+        out.push_str(&format!("{:<4}", label));
+
+        for week in 0..weeks {
+            let day = start + Duration::days((week * 7 + row) as i64);
+            if day > today {
+                out.push_str("  ");
+                continue;
+            }
+            let count = per_day.get(&day).copied().unwrap_or(0);
+            out.push_str(&format!("{} ", heat_cell(count, max)));
+        }
+        out.push('\n');
+    }
+
+    out.push_str(&format!(
+        "    Less {} {} {} {} {} More",
+        heat_cell(0, 4),
+        heat_cell(1, 4),
+        heat_cell(2, 4),
+        heat_cell(3, 4),
+        heat_cell(4, 4)
+    ));
+    out
+}
+
+fn heat_cell(count: usize, max: usize) -> String {
+    if count == 0 || max == 0 {
+        return "■".truecolor(45, 51, 59).to_string();
+    }
+    let level = (count * 4).div_ceil(max);
+    let (r, g, b) = match level {
+        1 => (14, 68, 41),
+        2 => (0, 109, 50),
+        3 => (38, 166, 65),
+        _ => (57, 211, 83),
+    };
+    "■".truecolor(r, g, b).to_string()
+}
+
+pub fn render_bars<'a>(rows: impl IntoIterator<Item = (&'a str, usize)>) -> String {
+    let rows: Vec<(&str, usize)> = rows.into_iter().collect();
+    let max = rows.iter().map(|(_, count)| *count).max().unwrap_or(0);
+    let label_width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+
+    let mut out = String::new();
+    for (label, count) in rows {
+        let width = (count * BAR_WIDTH).checked_div(max).unwrap_or(0);
+        out.push_str(&format!(
+            "{:<label_width$} {} {}\n",
+            label,
+            "█".repeat(width).green(),
+            count
+        ));
+    }
+    out
+}
+
+pub fn weekday_rows(stats: &ActivityStats) -> Vec<(&'static str, usize)> {
+    WEEKDAYS.iter().copied().zip(stats.per_weekday).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git_ops::DiffStat;
+    use chrono::{Local, TimeZone};
+
+    fn commit(day: u32, hour: u32, files: &[&str]) -> CommitLog {
+        CommitLog {
+            message: "work".to_string(),
+            author: "Alice".to_string(),
+            email: "alice@example.com".to_string(),
+            date: Local.with_ymd_and_hms(2026, 3, day, hour, 15, 0).unwrap(),
+            hash: "abcdef0".to_string(),
+            kind: None,
+            scope: None,
+            breaking: false,
+            tickets: Vec::new(),
+            stats: Some(DiffStat {
+                insertions: 10,
+                deletions: 2,
+                files: files.iter().map(|f| f.to_string()).collect(),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_compute_stats() {
+        let commits = vec![
+            ("api".to_string(), commit(2, 9, &["src/main.rs", "README.md"])),
+            ("api".to_string(), commit(2, 14, &["src/main.rs"])),
+            ("web".to_string(), commit(4, 9, &["index.html"])),
+        ];
+
+        let stats = compute(&commits, 2);
+        assert_eq!(stats.total_commits, 3);
+        assert_eq!(stats.per_repo["api"], 2);
+        assert_eq!(stats.per_weekday[0], 2);
+        assert_eq!(stats.per_weekday[2], 1);
+        assert_eq!(stats.per_hour[9], 2);
+        assert_eq!(stats.insertions, 30);
+        assert_eq!(stats.deletions, 6);
+        assert_eq!(
+            stats.top_files,
+            vec![("api/src/main.rs".to_string(), 2), ("api/README.md".to_string(), 1)]
+        );
+
+        let rows = csv_rows(&stats);
+        assert_eq!(rows[0], ("total", "commits".to_string(), 3));
+        assert!(rows.contains(&("repo", "web".to_string(), 1)));
+        assert!(rows.contains(&("weekday", "Mon".to_string(), 2)));
+        assert!(rows.contains(&("hour", "09".to_string(), 2)));
+        assert!(rows.contains(&("file", "api/src/main.rs".to_string(), 2)));
+    }
+
+    #[test]
+    fn test_heatmap_start_is_monday() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 5).unwrap();
+        assert_eq!(heatmap_start(today, 1), NaiveDate::from_ymd_opt(2026, 3, 2).unwrap());
+        assert_eq!(heatmap_start(today, 3), NaiveDate::from_ymd_opt(2026, 2, 16).unwrap());
+
+        let since = NaiveDate::from_ymd_opt(2026, 2, 20).unwrap();
+        assert_eq!(weeks_between(since, today), 3);
+        assert_eq!(heatmap_start(today, weeks_between(since, today)), heatmap_start(since, 1));
+        assert_eq!(weeks_between(today, today), 1);
+    }
+}
@@ -0,0 +1,348 @@
+use crate::db::{Database, ManuelEntry, NoteKind, SummaryRecord};
+use crate::migrations;
+use anyhow::{Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use clap::ValueEnum;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    Json,
+    Sqlite,
+}
+
+pub trait Storage {
+    fn path(&self) -> &Path;
+    fn load(&self) -> Result<Database>;
+    fn load_index(&self) -> Result<Database>;
+    fn save(&self, before: &Database, after: &Database) -> Result<()>;
+    fn notes_between(&self, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Result<Vec<ManuelEntry>>;
+    fn history_between(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<SummaryRecord>>;
+}
+
+pub fn sqlite_path(json_path: &Path) -> PathBuf {
+    json_path.with_extension("sqlite3")
+}
+
+pub fn detect(json_path: &Path) -> Backend {
+    if sqlite_path(json_path).exists() {
+        Backend::Sqlite
+    } else {
+        Backend::Json
+    }
+}
+
+pub fn open(json_path: &Path, backend: Backend) -> Box<dyn Storage> {
+    match backend {
+        Backend::Json => Box::new(JsonStore {
+            path: json_path.to_path_buf(),
+        }),
+        Backend::Sqlite => Box::new(SqliteStore {
+            path: sqlite_path(json_path),
+        }),
+    }
+}
+
+pub struct JsonStore {
+    path: PathBuf,
+}
+
+impl Storage for JsonStore {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn load(&self) -> Result<Database> {
+        if !self.path.exists() {
+            return Ok(Database::default());
+        }
+        Database::load_from(&self.path)
+    }
+
+    fn load_index(&self) -> Result<Database> {
+        self.load()
+    }
+
+    fn save(&self, _before: &Database, after: &Database) -> Result<()> {
+        after.save_to(&self.path)
+    }
+
+    fn notes_between(&self, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Result<Vec<ManuelEntry>> {
+        let mut notes: Vec<ManuelEntry> = self
+            .load()?
+            .entries
+            .into_iter()
+            .filter(|e| in_range(e.date, from, to))
+            .collect();
+        notes.sort_by_key(|e| e.date);
+        Ok(notes)
+    }
+
+    fn history_between(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<SummaryRecord>> {
+        let mut history: Vec<SummaryRecord> = self
+            .load()?
+            .history
+            .into_iter()
+            .filter(|h| in_range(h.date, from, to))
+            .collect();
+        history.sort_by_key(|h| h.date);
+        Ok(history)
+    }
+}
+
+pub struct SqliteStore {
+    path: PathBuf,
+}
+
+fn format_date(date: &DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+fn in_range(date: DateTime<Utc>, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> bool {
+    from.is_none_or(|from| date >= from) && to.is_none_or(|to| date < to)
+}
+
+fn parse_date(value: String) -> rusqlite::Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(&value)
+        .map(|date| date.with_timezone(&Utc))
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e)))
+}
+
+fn note_from_row(row: &rusqlite::Row) -> rusqlite::Result<ManuelEntry> {
+    let tags: String = row.get(3)?;
+    Ok(ManuelEntry {
+        id: row.get::<_, i64>(0)? as u64,
+        message: row.get(1)?,
+        date: parse_date(row.get(2)?)?,
+        tags: serde_json::from_str(&tags).unwrap_or_default(),
+        repo: row.get(4)?,
+        kind: match row.get::<_, String>(5)?.as_str() {
+            "activity" => NoteKind::Activity,
+            _ => NoteKind::Manual,
+        },
+    })
+}
+
+fn history_from_row(row: &rusqlite::Row) -> rusqlite::Result<SummaryRecord> {
+    Ok(SummaryRecord {
+        date: parse_date(row.get(0)?)?,
+        model: row.get(1)?,
+        summary: row.get(2)?,
+    })
+}
+
+impl SqliteStore {
+    fn connect(&self) -> Result<Connection> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut conn = Connection::open(&self.path)
+            .with_context(|| format!("Failed to open {:?}", self.path))?;
+        conn.busy_timeout(std::time::Duration::from_secs(10))?;
+        migrations::migrate_sqlite(&mut conn)?;
+        Ok(conn)
+    }
+
+    fn notes_query(&self, sql: &str, params: impl rusqlite::Params) -> Result<Vec<ManuelEntry>> {
+        let conn = self.connect()?;
+        let mut stmt = conn.prepare(sql)?;
+        let notes = stmt
+            .query_map(params, note_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(notes)
+    }
+
+    fn history_query(&self, sql: &str, params: impl rusqlite::Params) -> Result<Vec<SummaryRecord>> {
+        let conn = self.connect()?;
+        let mut stmt = conn.prepare(sql)?;
+        let history = stmt
+            .query_map(params, history_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(history)
+    }
+}
+
+impl Storage for SqliteStore {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn load(&self) -> Result<Database> {
+        let mut db = self.load_index()?;
+        db.entries = self.notes_query(
+            "SELECT id, message, date, tags, repo, kind FROM notes ORDER BY id",
+            [],
+        )?;
+        db.history = self.history_query("SELECT date, model, summary FROM history ORDER BY id", [])?;
+        Ok(db)
+    }
+
+    fn load_index(&self) -> Result<Database> {
+        let conn = self.connect()?;
+        let index: Option<String> = conn
+            .query_row("SELECT value FROM state WHERE key = 'index'", [], |row| row.get(0))
+            .optional()?;
+        match index {
+            Some(index) => serde_json::from_str(&index).context("Json error"),
+            None => Ok(Database::default()),
+        }
+    }
+
+    fn save(&self, before: &Database, after: &Database) -> Result<()> {
+        let mut conn = self.connect()?;
+        let tx = conn.transaction()?;
+
+        let mut index = serde_json::to_value(after)?;
+        if let Some(index) = index.as_object_mut() {
+            index.remove("entries");
+            index.remove("history");
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO state (key, value) VALUES ('index', ?1)",
+            params![index.to_string()],
+        )?;
+
+        let old: HashMap<u64, &ManuelEntry> = before.entries.iter().map(|e| (e.id, e)).collect();
+        for entry in &after.entries {
+            if old.get(&entry.id) == Some(&entry) {
+                continue;
+            }
+            tx.execute(
+                "INSERT OR REPLACE INTO notes (id, message, date, tags, repo, kind) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    entry.id as i64,
+                    entry.message,
+                    format_date(&entry.date),
+                    serde_json::to_string(&entry.tags)?,
+                    entry.repo,
+                    if entry.kind.is_manual() { "manual" } else { "activity" }
+                ],
+            )?;
+        }
+        let kept: HashSet<u64> = after.entries.iter().map(|e| e.id).collect();
+        for id in old.keys().filter(|id| !kept.contains(id)) {
+            tx.execute("DELETE FROM notes WHERE id = ?1", params![*id as i64])?;
+        }
+
+        let appended = if after.history.starts_with(&before.history) {
+            &after.history[before.history.len()..]
+        } else {
+            tx.execute("DELETE FROM history", [])?;
+            &after.history[..]
+        };
+        for record in appended {
+            tx.execute(
+                "INSERT INTO history (date, model, summary) VALUES (?1, ?2, ?3)",
+                params![format_date(&record.date), record.model, record.summary],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn notes_between(&self, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Result<Vec<ManuelEntry>> {
+        self.notes_query(
+            "SELECT id, message, date, tags, repo, kind FROM notes
+             WHERE (?1 IS NULL OR date >= ?1) AND (?2 IS NULL OR date < ?2)
+             ORDER BY date",
+            params![from.as_ref().map(format_date), to.as_ref().map(format_date)],
+        )
+    }
+
+    fn history_between(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<SummaryRecord>> {
+        self.history_query(
+            "SELECT date, model, summary FROM history
+             WHERE (?1 IS NULL OR date >= ?1) AND (?2 IS NULL OR date < ?2)
+             ORDER BY date",
+            params![from.as_ref().map(format_date), to.as_ref().map(format_date)],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    fn at(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap()
+    }
+
+    fn sample() -> Database {
+        let mut db = Database::default();
+        db.repositories.push(PathBuf::from("/work/api"));
+        db.add_note("first".to_string(), at(1), vec!["pairing".to_string()], None);
+        db.add_note("second".to_string(), at(3), Vec::new(), Some("api".to_string()));
+        db.add_note("third".to_string(), at(5), Vec::new(), None);
+        db.add_activity("Switched to branch fix".to_string(), at(6), "api".to_string());
+        db.history.push(SummaryRecord {
+            date: at(3),
+            model: "gpt".to_string(),
+            summary: "Did things.".to_string(),
+        });
+        db
+    }
+
+    #[test]
+    fn test_backends_agree() {
+        let temp_dir = TempDir::new().unwrap();
+        let json_path = temp_dir.path().join("db.json");
+        let db = sample();
+
+        for backend in [Backend::Json, Backend::Sqlite] {
+            let store = open(&json_path, backend);
+            store.save(&Database::default(), &db).unwrap();
+
+            let loaded = store.load().unwrap();
+            assert_eq!(loaded.repositories, db.repositories);
+            assert_eq!(loaded.entries, db.entries);
+            assert_eq!(loaded.history, db.history);
+            assert_eq!(loaded.next_note_id, db.next_note_id);
+
+            let notes = store.notes_between(Some(at(2)), Some(at(5))).unwrap();
+            assert_eq!(notes.len(), 1);
+            assert_eq!(notes[0].message, "second");
+            assert_eq!(store.notes_between(Some(at(3)), None).unwrap().len(), 3);
+            assert_eq!(store.history_between(None, Some(at(4))).unwrap().len(), 1);
+            assert!(store.history_between(Some(at(4)), None).unwrap().is_empty());
+        }
+        assert_eq!(detect(&json_path), Backend::Sqlite);
+    }
+
+    #[test]
+    fn test_sqlite_save_applies_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = open(&temp_dir.path().join("db.json"), Backend::Sqlite);
+        let before = sample();
+        store.save(&Database::default(), &before).unwrap();
+
+        let mut after = before.clone();
+        after.remove_entry(1);
+        after.find_entry_mut(2).unwrap().message = "edited".to_string();
+        after.add_summary("gpt".to_string(), "More things.".to_string());
+        store.save(&before, &after).unwrap();
+
+        let loaded = store.load().unwrap();
+        let messages: Vec<&str> = loaded.entries.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec!["edited", "third", "Switched to branch fix"]);
+        assert_eq!(loaded.entries[2].kind, NoteKind::Activity);
+        assert_eq!(loaded.history.len(), 2);
+        assert!(store.load_index().unwrap().entries.is_empty());
+    }
+}
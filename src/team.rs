@@ -0,0 +1,108 @@
+use crate::{git_ops::CommitLog, settings::TeamMember};
+use std::collections::{BTreeMap, HashMap};
+
+pub struct MemberActivity {
+    pub name: String,
+    pub commits: Vec<(String, CommitLog)>,
+}
+
+pub fn canonical_member<'a>(members: &'a [TeamMember], name: &str, email: &str) -> Option<&'a str> {
+    let name = name.to_lowercase();
+    let email = email.to_lowercase();
+
+    members
+        .iter()
+        .find(|m| {
+            m.name.to_lowercase() == name
+                || m.aliases.iter().any(|alias| {
+                    let alias = alias.to_lowercase();
+                    alias == name || alias == email
+                })
+        })
+        .map(|m| m.name.as_str())
+}
+
+pub fn group_by_author(
+    members: &[TeamMember],
+    commits: Vec<(String, CommitLog)>,
+) -> Vec<MemberActivity> {
+    let mut names_by_email: HashMap<String, String> = HashMap::new();
+    let mut grouped: BTreeMap<String, Vec<(String, CommitLog)>> = BTreeMap::new();
+
+    for m in members {
+        grouped.entry(m.name.clone()).or_default();
+    }
+
+    for (repo, commit) in commits {
+        let key = match canonical_member(members, &commit.author, &commit.email) {
+            Some(name) => name.to_string(),
+            None => names_by_email
+                .entry(commit.email.to_lowercase())
+                .or_insert_with(|| commit.author.clone())
+                .clone(),
+        };
+        grouped.entry(key).or_default().push((repo, commit));
+    }
+
+    grouped
+        .into_iter()
+        .map(|(name, mut commits)| {
+            commits.sort_by_key(|(_, c)| c.date);
+            MemberActivity { name, commits }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Local};
+
+    fn commit(author: &str, email: &str, minutes_ago: i64) -> (String, CommitLog) {
+        (
+            "api".to_string(),
+            CommitLog {
+                message: format!("work by {}", author),
+                author: author.to_string(),
+                email: email.to_string(),
+                date: Local::now() - Duration::minutes(minutes_ago),
+                hash: "abcdef0".to_string(),
+                kind: None,
+                scope: None,
+                breaking: false,
+                tickets: Vec::new(),
+                stats: None,
+            },
+        )
+    }
+
+    #[test]
+    fn test_group_by_author_uses_aliases_and_emails() {
+        let members = vec![
+            TeamMember {
+                name: "Alice Doe".to_string(),
+                aliases: vec!["alice@corp.com".to_string(), "adoe".to_string()],
+            },
+            TeamMember {
+                name: "Carol".to_string(),
+                aliases: Vec::new(),
+            },
+        ];
+
+        let commits = vec![
+            commit("adoe", "a@home.net", 10),
+            commit("Alice", "alice@corp.com", 30),
+            commit("Bob", "bob@corp.com", 20),
+            commit("bobby", "BOB@corp.com", 5),
+        ];
+
+        let groups = group_by_author(&members, commits);
+        let names: Vec<&str> = groups.iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(names, vec!["Alice Doe", "Bob", "Carol"]);
+
+        assert_eq!(groups[0].commits.len(), 2);
+        assert_eq!(groups[0].commits[0].1.author, "Alice");
+        assert_eq!(groups[1].commits.len(), 2);
+        assert!(groups[2].commits.is_empty());
+    }
+}
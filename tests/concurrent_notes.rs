@@ -0,0 +1,38 @@
+use std::fs;
+use std::process::Command;
+use tempfile::TempDir;
+
+#[test]
+fn concurrent_notes_are_not_lost() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("db.json");
+    let processes = 16;
+
+    let children: Vec<_> = (0..processes)
+        .map(|i| {
+            Command::new(env!("CARGO_BIN_EXE_yestergit"))
+                .args(["note", &format!("note {}", i)])
+                .env("YESTERGIT_DB_PATH", &db_path)
+                .spawn()
+                .unwrap()
+        })
+        .collect();
+
+    for mut child in children {
+        assert!(child.wait().unwrap().success());
+    }
+
+    let db: serde_json::Value = serde_json::from_str(&fs::read_to_string(&db_path).unwrap()).unwrap();
+    let entries = db["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), processes);
+
+    let mut ids: Vec<u64> = entries.iter().map(|e| e["id"].as_u64().unwrap()).collect();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), processes);
+
+    for i in 0..processes {
+        let message = format!("note {}", i);
+        assert!(entries.iter().any(|e| e["message"] == message.as_str()));
+    }
+}
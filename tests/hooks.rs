@@ -0,0 +1,98 @@
+use std::path::Path;
+use std::process::{Command, Output};
+use std::thread;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+fn yestergit(home: &Path, dir: &Path, args: &[&str]) -> Output {
+    let output = Command::new(env!("CARGO_BIN_EXE_yestergit"))
+        .args(args)
+        .current_dir(dir)
+        .env("HOME", home)
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .env_remove("YESTERGIT_DB_PATH")
+        .env_remove("RECALL_DB_PATH")
+        .env_remove("YESTERGIT_PROFILE")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    output
+}
+
+fn git(home: &Path, dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .env("HOME", home)
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .env("GIT_AUTHOR_NAME", "Dev")
+        .env("GIT_AUTHOR_EMAIL", "dev@example.com")
+        .env("GIT_COMMITTER_NAME", "Dev")
+        .env("GIT_COMMITTER_EMAIL", "dev@example.com")
+        .env_remove("YESTERGIT_DB_PATH")
+        .env_remove("RECALL_DB_PATH")
+        .env_remove("YESTERGIT_PROFILE")
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+fn activity(home: &Path, dir: &Path, expected: usize) -> Vec<String> {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        let output = yestergit(home, dir, &["--format", "json", "note", "list", "--activity"]);
+        let notes: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap();
+        if notes.len() >= expected || Instant::now() > deadline {
+            return notes.iter().map(|n| n["message"].as_str().unwrap().to_string()).collect();
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn hooks_record_activity_and_uninstall_cleanly() {
+    let home = TempDir::new().unwrap();
+    let repo = home.path().join("src/api");
+    std::fs::create_dir_all(&repo).unwrap();
+    git(home.path(), &repo, &["init", "-q", "-b", "main"]);
+
+    let hook = repo.join(".git/hooks/post-commit");
+    std::fs::write(&hook, "#!/bin/sh\necho ran >> \"$(git rev-parse --git-dir)/own-hook\"\n").unwrap();
+    Command::new("chmod").arg("+x").arg(&hook).status().unwrap();
+
+    yestergit(home.path(), home.path(), &["scan", "--path", home.path().join("src").to_str().unwrap()]);
+    let installed = yestergit(home.path(), &repo, &["hooks", "install"]);
+    assert!(String::from_utf8_lossy(&installed.stdout).contains("post-commit hook still runs first"));
+
+    git(home.path(), &repo, &["commit", "-q", "--allow-empty", "-m", "PROJ-7 first"]);
+    git(home.path(), &repo, &["checkout", "-q", "-b", "PROJ-8-spike"]);
+    git(home.path(), &repo, &["commit", "-q", "--allow-empty", "-m", "try it"]);
+    git(home.path(), &repo, &["checkout", "-q", "main"]);
+    git(home.path(), &repo, &["reset", "-q", "--hard", "HEAD"]);
+
+    let messages = activity(home.path(), &repo, 4);
+    assert_eq!(messages.len(), 4, "{:?}", messages);
+    let has = |prefix: &str, suffix: &str| messages.iter().any(|m| m.starts_with(prefix) && m.ends_with(suffix));
+    assert!(has("Committed ", " on main: PROJ-7 first"), "{:?}", messages);
+    assert!(has("Committed ", " on PROJ-8-spike: try it"), "{:?}", messages);
+    assert!(has("Switched to PROJ-8-spike", "") && has("Switched to main", ""), "{:?}", messages);
+    assert_eq!(std::fs::read_to_string(repo.join(".git/own-hook")).unwrap(), "ran\nran\n");
+
+    let report = yestergit(home.path(), &repo, &["--format", "json"]);
+    let report: serde_json::Value = serde_json::from_slice(&report.stdout).unwrap();
+    let types: Vec<&str> = report["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["type"].as_str().unwrap())
+        .collect();
+    assert_eq!(types.iter().filter(|t| **t == "commit").count(), 1);
+    assert_eq!(types.iter().filter(|t| **t == "activity").count(), 3);
+
+    yestergit(home.path(), home.path(), &["hooks", "uninstall", "--all"]);
+    assert!(std::fs::read_to_string(&hook).unwrap().contains("own-hook"));
+    assert!(!repo.join(".git/hooks/post-checkout").exists());
+    assert!(!repo.join(".git/hooks/post-commit.yestergit-chained").exists());
+}
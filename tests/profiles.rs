@@ -0,0 +1,75 @@
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+fn yestergit(config_home: &Path, args: &[&str], profile_env: Option<&str>) -> Output {
+    yestergit_with_db(config_home, args, profile_env, None)
+}
+
+fn yestergit_with_db(
+    config_home: &Path,
+    args: &[&str],
+    profile_env: Option<&str>,
+    db: Option<&Path>,
+) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_yestergit"));
+    command
+        .args(args)
+        .env("HOME", config_home)
+        .env("XDG_CONFIG_HOME", config_home.join(".config"))
+        .env_remove("YESTERGIT_DB_PATH")
+        .env_remove("RECALL_DB_PATH")
+        .env_remove("YESTERGIT_PROFILE");
+    if let Some(profile) = profile_env {
+        command.env("YESTERGIT_PROFILE", profile);
+    }
+    if let Some(db) = db {
+        command.env("YESTERGIT_DB_PATH", db);
+    }
+    let output = command.output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    output
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn profiles_keep_notes_and_settings_apart() {
+    let home = TempDir::new().unwrap();
+    let config_dir = home.path().join(".config/yestergit");
+
+    yestergit(home.path(), &["note", "personal note"], None);
+    yestergit(home.path(), &["--profile", "work", "note", "work note"], None);
+    yestergit(home.path(), &["config", "--set-model", "work-model"], Some("work"));
+
+    let default_db = std::fs::read_to_string(config_dir.join("db.json")).unwrap();
+    let work_db = std::fs::read_to_string(config_dir.join("profiles/work/db.json")).unwrap();
+    assert!(default_db.contains("personal note") && !default_db.contains("work note"));
+    assert!(work_db.contains("work note") && !work_db.contains("personal note"));
+
+    let work_config = std::fs::read_to_string(config_dir.join("config-work.toml")).unwrap();
+    assert!(work_config.contains("work-model"));
+    assert!(!config_dir.join("config.toml").exists());
+
+    let listed = yestergit(home.path(), &["note", "list"], Some("work"));
+    let listed = String::from_utf8_lossy(&listed.stdout);
+    assert!(listed.contains("work note") && !listed.contains("personal note"));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn db_path_override_is_scoped_per_profile() {
+    let home = TempDir::new().unwrap();
+    let db = home.path().join("shared/db.json");
+
+    yestergit_with_db(home.path(), &["note", "personal note"], None, Some(&db));
+    yestergit_with_db(home.path(), &["--profile", "work", "note", "work note"], None, Some(&db));
+
+    let default_db = std::fs::read_to_string(&db).unwrap();
+    let work_db = std::fs::read_to_string(home.path().join("shared/db-work.json")).unwrap();
+    assert!(default_db.contains("personal note") && !default_db.contains("work note"));
+    assert!(work_db.contains("work note") && !work_db.contains("personal note"));
+
+    let listed = yestergit_with_db(home.path(), &["note", "list"], Some("work"), Some(&db));
+    let listed = String::from_utf8_lossy(&listed.stdout);
+    assert!(listed.contains("work note") && !listed.contains("personal note"));
+}
@@ -0,0 +1,53 @@
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+fn yestergit(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_yestergit"))
+        .args(args)
+        .env("HOME", home)
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .env_remove("YESTERGIT_DB_PATH")
+        .env_remove("RECALL_DB_PATH")
+        .env_remove("YESTERGIT_PROFILE")
+        .output()
+        .unwrap()
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn unreadable_repos_are_reported() {
+    let home = TempDir::new().unwrap();
+    let src = home.path().join("src");
+    git2::Repository::init(src.join("healthy")).unwrap();
+    git2::Repository::init(src.join("broken")).unwrap();
+
+    let scanned = yestergit(home.path(), &["scan", "--path", src.to_str().unwrap()]);
+    assert!(scanned.status.success());
+    std::fs::remove_dir_all(src.join("broken")).unwrap();
+
+    let report = yestergit(home.path(), &["--format", "json"]);
+    assert!(report.status.success(), "{}", String::from_utf8_lossy(&report.stderr));
+    let json: serde_json::Value = serde_json::from_slice(&report.stdout).unwrap();
+    let errors = json["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0]["repo"], "broken");
+    assert!(!errors[0]["causes"].as_array().unwrap().is_empty());
+
+    for command in ["hours", "stats"] {
+        let output = yestergit(home.path(), &[command, "--format", "json"]);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["errors"][0]["repo"], "broken", "{}", command);
+    }
+
+    let table = yestergit(home.path(), &[]);
+    let warnings = String::from_utf8_lossy(&table.stderr);
+    assert!(warnings.contains("1 repositories could not be read") && !warnings.contains("caused by"));
+
+    let verbose = yestergit(home.path(), &["--verbose"]);
+    assert!(String::from_utf8_lossy(&verbose.stderr).contains("caused by"));
+
+    let strict = yestergit(home.path(), &["--strict"]);
+    assert!(!strict.status.success());
+}